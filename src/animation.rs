@@ -1,38 +1,207 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
 
 pub struct AnimationPlugin;
 
 impl Plugin for AnimationPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, animate_sprites);
+        app.add_event::<AnimationFinished>()
+            .add_systems(Update, animate_sprites);
     }
 }
 
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum AnimationMode {
+    #[default]
+    Loop,
+    /// Play through once and hold on the last frame.
+    Once,
+    /// Play forward to the last frame, then back to the first, and repeat.
+    PingPong,
+}
+
 #[derive(Clone, Component)]
 pub struct AnimationIndices {
     pub first: usize,
     pub last: usize,
+    pub mode: AnimationMode,
+}
+
+impl AnimationIndices {
+    /// Whether a one-shot clip has reached its last frame.
+    pub fn is_finished(&self, atlas: &TextureAtlas) -> bool {
+        self.mode == AnimationMode::Once && atlas.index == self.last
+    }
 }
 
 #[derive(Component, Deref, DerefMut)]
-pub struct AnimationTimer(pub Timer);
+pub struct AnimationTimer {
+    #[deref]
+    pub timer: Timer,
+    /// Whether a ping-pong clip is currently playing backwards.
+    pub reversed: bool,
+}
+
+impl AnimationTimer {
+    pub fn from_seconds(seconds: f32) -> Self {
+        Self {
+            timer: Timer::from_seconds(seconds, TimerMode::Repeating),
+            reversed: false,
+        }
+    }
+}
+
+/// Per-frame hold times in seconds, starting at `AnimationIndices::first`.
+/// Without this the `AnimationTimer` interval is used for every frame.
+#[derive(Clone, Component, Deref)]
+pub struct FrameDurations(pub Vec<f32>);
+
+/// Playback rate multiplier for an animated entity, where `1.0` is normal speed.
+#[derive(Component, Deref, DerefMut)]
+pub struct AnimationSpeed(pub f32);
+
+/// Sent on the tick an animated entity reaches the last frame of its clip.
+#[derive(Event)]
+pub struct AnimationFinished {
+    pub entity: Entity,
+    pub indices: AnimationIndices,
+}
+
+/// Optional components that tweak how a clip is played back.
+type Playback<'a> = (Option<&'a FrameDurations>, Option<&'a AnimationSpeed>);
 
 fn animate_sprites(
     time: Res<Time>,
+    mut events: EventWriter<AnimationFinished>,
     mut query: Query<(
+        Entity,
         &mut AnimationIndices,
         &mut AnimationTimer,
         &mut TextureAtlas,
+        Playback,
     )>,
 ) {
-    for (indices, mut timer, mut atlas) in &mut query {
-        timer.tick(time.delta());
+    for (entity, indices, mut timer, mut atlas, (durations, speed)) in &mut query {
+        if indices.is_finished(&atlas) {
+            continue;
+        }
+
+        let speed = speed.map_or(1.0, |speed| speed.0);
+        timer.tick(time.delta().mul_f32(speed));
         if timer.just_finished() {
-            atlas.index = if atlas.index >= indices.last || atlas.index < indices.first {
-                indices.first
+            atlas.index = next_frame(&indices, atlas.index, &mut timer.reversed);
+
+            if let Some(seconds) = durations.and_then(|d| d.get(atlas.index - indices.first)) {
+                timer.set_duration(Duration::from_secs_f32(*seconds));
+            }
+
+            if atlas.index == indices.last {
+                events.send(AnimationFinished {
+                    entity,
+                    indices: indices.clone(),
+                });
+            }
+        }
+    }
+}
+
+fn next_frame(indices: &AnimationIndices, index: usize, reversed: &mut bool) -> usize {
+    if index > indices.last || index < indices.first {
+        *reversed = false;
+        return indices.first;
+    }
+
+    match indices.mode {
+        // Turn around on the endpoints so they aren't shown twice in a row.
+        AnimationMode::PingPong if indices.first < indices.last => {
+            if index == indices.last {
+                *reversed = true;
+            } else if index == indices.first {
+                *reversed = false;
+            }
+
+            if *reversed {
+                index - 1
             } else {
-                atlas.index + 1
-            };
+                index + 1
+            }
+        }
+        _ if index >= indices.last => indices.first,
+        _ => index + 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: f32 = 0.1;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.init_resource::<Time>().add_plugins(AnimationPlugin);
+        app
+    }
+
+    fn spawn_clip(app: &mut App, first: usize, last: usize, mode: AnimationMode) -> Entity {
+        app.world
+            .spawn((
+                AnimationIndices { first, last, mode },
+                AnimationTimer::from_seconds(FRAME),
+                TextureAtlas {
+                    layout: Handle::default(),
+                    index: first,
+                },
+            ))
+            .id()
+    }
+
+    /// Advance time by one frame interval and return how many clips finished.
+    fn step(app: &mut App) -> usize {
+        app.world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs_f32(FRAME));
+        app.update();
+        app.world
+            .resource_mut::<Events<AnimationFinished>>()
+            .drain()
+            .count()
+    }
+
+    fn index(app: &App, entity: Entity) -> usize {
+        app.world.get::<TextureAtlas>(entity).unwrap().index
+    }
+
+    #[test]
+    fn looping_clip_finishes_once_per_cycle() {
+        let mut app = app();
+        spawn_clip(&mut app, 0, 3, AnimationMode::Loop);
+
+        for _ in 0..5 {
+            let finished: usize = (0..4).map(|_| step(&mut app)).sum();
+            assert_eq!(finished, 1);
         }
     }
+
+    #[test]
+    fn once_clip_holds_until_switched_to_loop() {
+        let mut app = app();
+        let entity = spawn_clip(&mut app, 0, 2, AnimationMode::Once);
+
+        let finished: usize = (0..2).map(|_| step(&mut app)).sum();
+        assert_eq!(finished, 1);
+        assert_eq!(index(&app, entity), 2);
+
+        for _ in 0..3 {
+            assert_eq!(step(&mut app), 0);
+            assert_eq!(index(&app, entity), 2);
+        }
+
+        app.world.get_mut::<AnimationIndices>(entity).unwrap().mode = AnimationMode::Loop;
+        step(&mut app);
+        assert_eq!(index(&app, entity), 0);
+        step(&mut app);
+        assert_eq!(index(&app, entity), 1);
+    }
 }
@@ -1,35 +1,232 @@
 use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
+use bevy_kira_audio::{AudioChannel, AudioControl, AudioInstance, AudioTween};
+use bevy_pkv::PkvStore;
 use bevy_rapier2d::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 pub struct BoulderPlugin;
 
-use crate::GameState;
+use crate::{
+    camera::{CameraShake, MainCamera},
+    difficulty::Difficulty,
+    ground::rest_on_start_slope,
+    particles::Particles,
+    player::{Fatigue, FatigueConfig, Player, PLAYER_SPAWN},
+    DistanceTraveled, GameState, SoundFX, WINDOW_WIDTH,
+};
+
+/// PkvStore key for the last chosen `BoulderSize`.
+const BOULDER_SIZE_KEY: &str = "boulder_size";
+
+/// The boulder grows every this many meters...
+const GROWTH_DISTANCE: f32 = 100.;
+/// ...by this fraction of its starting radius...
+const GROWTH_PER_STEP: f32 = 0.1;
+/// ...up to this many times, so physics stays stable.
+const MAX_GROWTH_STEPS: u32 = 4;
+
+/// Contact force past which a hit on the boulder shakes the camera. Resting on
+/// flat ground the boulder presses down with roughly 700k.
+const HARD_IMPACT_FORCE: f32 = 2_000_000.;
+
+/// How well the boulder grips the slope. Averaged with the ground's 0.7, this
+/// is enough that it rolls rather than skids when let go, but it still slides
+/// a little when shoved over rocks.
+const BOULDER_FRICTION: f32 = 0.9;
+/// How much the boulder bounces. A stone this heavy should land with a thud
+/// and stay down, not bounce off rocks back onto the player.
+const BOULDER_RESTITUTION: f32 = 0.05;
+
+/// Distance from the player's spawn point to the back of the boulder: half the
+/// player's width and a little room, so they start just behind it.
+const BOULDER_SPAWN_GAP: f32 = 20.;
+
+/// Speed, in pixels per second, below which the boulder leaves no dust.
+const DUST_MIN_SPEED: f32 = 20.;
+/// Seconds between bursts of dust from under the boulder.
+const DUST_INTERVAL: f32 = 0.08;
+/// Most puffs per contact in a burst, however fast the boulder rolls.
+const MAX_DUST_PER_CONTACT: usize = 4;
+
+/// Speed, in pixels per second, below which the rumble is silent...
+const RUMBLE_MIN_SPEED: f32 = 10.;
+/// ...and at which it reaches full volume.
+const RUMBLE_FULL_SPEED: f32 = 300.;
+
+/// Fatigue at which the boulder starts to slip back toward the player...
+const SLIP_START_FATIGUE: f32 = 70.;
+/// ...and the extra downhill pull, in pixels per second squared, by the time
+/// the player collapses.
+const SLIP_ACCELERATION: f32 = 150.;
 
 #[derive(Component)]
 pub struct Boulder;
 
+/// Which boulder to push, chosen on the main menu.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum BoulderSize {
+    Small,
+    #[default]
+    Medium,
+    Large,
+}
+
+impl BoulderSize {
+    fn next(self) -> Self {
+        match self {
+            BoulderSize::Small => BoulderSize::Medium,
+            BoulderSize::Medium => BoulderSize::Large,
+            BoulderSize::Large => BoulderSize::Small,
+        }
+    }
+
+    /// The text for the main menu button.
+    pub fn label(self) -> String {
+        format!("Boulder: {self:?}")
+    }
+
+    /// Radius at the bottom of the hill, before it grows.
+    pub fn radius(self) -> f32 {
+        match self {
+            BoulderSize::Small => 48.,
+            BoulderSize::Medium => 64.,
+            BoulderSize::Large => 80.,
+        }
+    }
+
+    /// Mass before it grows. The medium boulder is 2500 lbs in kg, and the
+    /// others go with their area.
+    fn mass(self) -> f32 {
+        match self {
+            BoulderSize::Small => 638.,
+            BoulderSize::Medium => 1134.,
+            BoulderSize::Large => 1772.,
+        }
+    }
+
+    /// Tint over the stone texture: sandy when small, darker when large.
+    fn color(self) -> Color {
+        match self {
+            BoulderSize::Small => Color::rgb(1.0, 0.95, 0.85),
+            BoulderSize::Medium => Color::WHITE,
+            BoulderSize::Large => Color::rgb(0.7, 0.7, 0.75),
+        }
+    }
+
+    /// Multiplier on how fast pushing tires the player out.
+    pub fn fatigue_scale(self) -> f32 {
+        match self {
+            BoulderSize::Small => 0.75,
+            BoulderSize::Medium => 1.0,
+            BoulderSize::Large => 1.35,
+        }
+    }
+}
+
+/// The main menu button that cycles through the boulder sizes.
+#[derive(Component)]
+pub struct BoulderSizeButton;
+
+/// How many times the boulder has grown.
+#[derive(Component, Default)]
+struct Growth(u32);
+
+impl Growth {
+    /// Size relative to the starting radius.
+    fn scale(&self) -> f32 {
+        1. + GROWTH_PER_STEP * self.0 as f32
+    }
+
+    /// Mass in kg at this size. Mass goes with area, as though it were the same
+    /// stone all the way through.
+    fn mass(&self, size: BoulderSize, difficulty: Difficulty) -> f32 {
+        size.mass() * difficulty.mass_scale() * self.scale() * self.scale()
+    }
+}
+
 impl Plugin for BoulderPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnExit(GameState::MainMenu), spawn_boulder)
-            .add_systems(OnExit(GameState::InGame), freeze_boulder)
-            .add_systems(OnEnter(GameState::InGame), unfreeze_boulder);
+        app.add_systems(Startup, load_boulder_size)
+            .add_systems(
+                Update,
+                boulder_size_button_system.run_if(in_state(GameState::MainMenu)),
+            )
+            .add_systems(
+                OnTransition {
+                    from: GameState::MainMenu,
+                    to: GameState::InGame,
+                },
+                (spawn_boulder, start_rumble),
+            )
+            // A fresh boulder for each retry; the rumble carries on from the last
+            // run, silenced while out of play.
+            .add_systems(
+                OnTransition {
+                    from: GameState::Cleanup,
+                    to: GameState::InGame,
+                },
+                spawn_boulder,
+            )
+            .add_systems(OnExit(GameState::InGame), (freeze_boulder, silence_rumble))
+            .add_systems(
+                OnEnter(GameState::InGame),
+                (unfreeze_boulder, resume_rumble),
+            )
+            .add_systems(
+                Update,
+                (
+                    shake_on_impact,
+                    grow_boulder,
+                    trail_dust,
+                    update_rumble,
+                    slip_boulder,
+                )
+                    .run_if(in_state(GameState::InGame)),
+            );
     }
 }
 
-fn freeze_boulder(mut commands: Commands, boulder: Query<Entity, With<Boulder>>) {
-    if boulder.is_empty() {
-        return;
+fn load_boulder_size(mut commands: Commands, pkv: Res<PkvStore>) {
+    let size = pkv.get::<BoulderSize>(BOULDER_SIZE_KEY).unwrap_or_default();
+    commands.insert_resource(size);
+}
+
+/// The boulder size button, if its `Interaction` changed this frame.
+type ChangedButton = (Changed<Interaction>, With<BoulderSizeButton>);
+
+fn boulder_size_button_system(
+    mut size: ResMut<BoulderSize>,
+    mut pkv: ResMut<PkvStore>,
+    interaction_query: Query<(&Interaction, &Children), ChangedButton>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (interaction, children) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        *size = size.next();
+        if let Err(err) = pkv.set(BOULDER_SIZE_KEY, &*size) {
+            warn!("failed to save boulder size: {err}");
+        }
+
+        if let Ok(mut text) = text_query.get_mut(children[0]) {
+            text.sections[0].value = size.label();
+        }
     }
+}
 
-    commands.entity(boulder.single()).insert(RigidBody::Fixed);
+fn freeze_boulder(mut commands: Commands, boulder: Query<Entity, With<Boulder>>) {
+    if let Ok(boulder) = boulder.get_single() {
+        commands.entity(boulder).insert(RigidBody::Fixed);
+    }
 }
 
 fn unfreeze_boulder(mut commands: Commands, boulder: Query<Entity, With<Boulder>>) {
-    if boulder.is_empty() {
-        return;
+    if let Ok(boulder) = boulder.get_single() {
+        commands.entity(boulder).insert(RigidBody::Dynamic);
     }
-
-    commands.entity(boulder.single()).insert(RigidBody::Dynamic);
 }
 
 fn spawn_boulder(
@@ -37,17 +234,210 @@ fn spawn_boulder(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     asset_server: Res<AssetServer>,
+    difficulty: Res<Difficulty>,
+    size: Res<BoulderSize>,
 ) {
+    let x = PLAYER_SPAWN.x + BOULDER_SPAWN_GAP + size.radius();
+    let position = rest_on_start_slope(x, size.radius());
+
     commands
         .spawn(MaterialMesh2dBundle {
-            mesh: meshes.add(Circle { radius: 64. }).into(),
-            material: materials.add(asset_server.load("textures/stone.png")),
+            mesh: meshes
+                .add(Circle {
+                    radius: size.radius(),
+                })
+                .into(),
+            material: materials.add(ColorMaterial {
+                color: size.color(),
+                texture: Some(asset_server.load("textures/stone.png")),
+            }),
             // material: materials.add(Color::BLUE),
-            transform: Transform::from_xyz(0.0, 0.0, 5.0),
+            transform: Transform::from_translation(position.extend(5.0)),
             ..default()
         })
         .insert(RigidBody::Dynamic)
-        .insert(Collider::ball(64.))
-        .insert(AdditionalMassProperties::Mass(1134.)) // 2500 lbs in kg
+        .insert(Collider::ball(size.radius()))
+        .insert(Friction::coefficient(BOULDER_FRICTION))
+        .insert(Restitution::coefficient(BOULDER_RESTITUTION))
+        // The transform's scale only grows the mesh; `grow_boulder` sizes the collider.
+        .insert(ColliderScale::Absolute(Vec2::ONE))
+        .insert(AdditionalMassProperties::Mass(
+            size.mass() * difficulty.mass_scale(),
+        ))
+        .insert(Velocity::default())
+        .insert(ExternalForce::default())
+        .insert(Growth::default())
+        .insert(ActiveEvents::CONTACT_FORCE_EVENTS)
+        .insert(ContactForceEventThreshold(HARD_IMPACT_FORCE))
         .insert(Boulder);
 }
+
+fn shake_on_impact(
+    mut contact_forces: EventReader<ContactForceEvent>,
+    mut shake: ResMut<CameraShake>,
+    boulder: Query<Entity, With<Boulder>>,
+) {
+    for event in contact_forces.read() {
+        if boulder.contains(event.collider1) || boulder.contains(event.collider2) {
+            shake.add_trauma(0.2 * event.total_force_magnitude / HARD_IMPACT_FORCE);
+        }
+    }
+}
+
+/// Throw up dust where the boulder meets the ground, more the faster it rolls.
+fn trail_dust(
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    mut particles: Particles,
+    boulder: Query<(Entity, &Velocity), With<Boulder>>,
+    player: Query<(), With<Player>>,
+    mut since_last: Local<f32>,
+) {
+    let Ok((boulder, velocity)) = boulder.get_single() else {
+        return;
+    };
+
+    *since_last += time.delta_seconds();
+    let speed = velocity.linvel.length();
+    if speed < DUST_MIN_SPEED || *since_last < DUST_INTERVAL {
+        return;
+    }
+    *since_last = 0.;
+
+    let puffs = ((speed / 100.).ceil() as usize).min(MAX_DUST_PER_CONTACT);
+    // Kicked back the way the boulder came.
+    let behind = -velocity.linvel.x.signum();
+    let mut rng = rand::thread_rng();
+
+    for pair in rapier_context.contact_pairs_with(boulder) {
+        let other = if pair.collider1() == boulder {
+            pair.collider2()
+        } else {
+            pair.collider1()
+        };
+        if player.contains(other) || !pair.has_any_active_contacts() {
+            continue;
+        }
+
+        for manifold in pair.manifolds() {
+            for contact in manifold.solver_contacts() {
+                for _ in 0..puffs {
+                    let drift = Vec2::new(
+                        behind * rng.gen_range(10.0..40.0),
+                        rng.gen_range(10.0..30.0),
+                    );
+                    particles.dust(contact.point(), drift, rng.gen_range(3.0..6.0), 0.7);
+                }
+            }
+        }
+    }
+}
+
+/// The looping sound of the boulder rolling.
+#[derive(Resource)]
+struct Rumble(Handle<AudioInstance>);
+
+fn start_rumble(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    sound_fx: Res<AudioChannel<SoundFX>>,
+) {
+    let handle = sound_fx
+        .play(asset_server.load("sfx/rumble.wav"))
+        .looped()
+        .with_volume(0.)
+        .handle();
+    commands.insert_resource(Rumble(handle));
+}
+
+/// Pan the rumble toward whichever side of the screen the boulder is on, and
+/// turn it up the faster it rolls.
+fn update_rumble(
+    rumble: Res<Rumble>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+    boulder: Query<(&Transform, &Velocity), With<Boulder>>,
+    camera: Query<&Transform, With<MainCamera>>,
+) {
+    let (Ok((boulder, velocity)), Ok(camera)) = (boulder.get_single(), camera.get_single()) else {
+        return;
+    };
+    let Some(instance) = audio_instances.get_mut(&rumble.0) else {
+        return;
+    };
+
+    // 0 is hard left, 1 hard right; the screen edges are the extremes.
+    let offset = boulder.translation.x - camera.translation.x;
+    let panning = (0.5 + offset / WINDOW_WIDTH).clamp(0., 1.);
+    let volume = ((velocity.linvel.length() - RUMBLE_MIN_SPEED)
+        / (RUMBLE_FULL_SPEED - RUMBLE_MIN_SPEED))
+        .clamp(0., 1.);
+
+    instance.set_panning(panning as f64, AudioTween::default());
+    instance.set_volume(volume as f64, AudioTween::default());
+}
+
+/// Quiet the rumble under the menus, so it doesn't hold its last volume there
+/// or carry it into the next run.
+fn silence_rumble(rumble: Option<Res<Rumble>>, mut audio_instances: ResMut<Assets<AudioInstance>>) {
+    let Some(instance) = rumble.and_then(|rumble| audio_instances.get_mut(&rumble.0)) else {
+        return;
+    };
+    instance.set_volume(0., AudioTween::default());
+    instance.pause(AudioTween::default());
+}
+
+fn resume_rumble(rumble: Option<Res<Rumble>>, mut audio_instances: ResMut<Assets<AudioInstance>>) {
+    let Some(instance) = rumble.and_then(|rumble| audio_instances.get_mut(&rumble.0)) else {
+        return;
+    };
+    instance.resume(AudioTween::default());
+}
+
+/// Make the boulder bigger and heavier the further up the hill it gets, and
+/// back to normal once the distance is reset.
+fn grow_boulder(
+    distance_traveled: Res<DistanceTraveled>,
+    difficulty: Res<Difficulty>,
+    size: Res<BoulderSize>,
+    mut commands: Commands,
+    mut boulder: Query<(Entity, &mut Transform, &mut Growth), With<Boulder>>,
+) {
+    let Ok((entity, mut transform, mut growth)) = boulder.get_single_mut() else {
+        return;
+    };
+
+    let steps = ((distance_traveled.0 / 64. / GROWTH_DISTANCE) as u32).min(MAX_GROWTH_STEPS);
+    if steps == growth.0 {
+        return;
+    }
+    growth.0 = steps;
+
+    let scale = growth.scale();
+    transform.scale = Vec3::new(scale, scale, 1.);
+    commands.entity(entity).insert((
+        Collider::ball(size.radius() * scale),
+        AdditionalMassProperties::Mass(growth.mass(*size, *difficulty)),
+    ));
+}
+
+/// Loosen the player's grip as they tire: past `SLIP_START_FATIGUE` the
+/// boulder is pulled back downhill, harder the closer they are to collapsing,
+/// so letting go sends it rolling back all the faster.
+fn slip_boulder(
+    config: Res<FatigueConfig>,
+    difficulty: Res<Difficulty>,
+    size: Res<BoulderSize>,
+    player: Query<&Fatigue, With<Player>>,
+    mut boulder: Query<(&mut ExternalForce, &Growth), With<Boulder>>,
+) {
+    let (Ok(fatigue), Ok((mut force, growth))) = (player.get_single(), boulder.get_single_mut())
+    else {
+        return;
+    };
+
+    let t = ((fatigue.0 - SLIP_START_FATIGUE) / (config.collapse_threshold - SLIP_START_FATIGUE))
+        .clamp(0., 1.);
+    // Ease in so there's no sudden lurch as it kicks in.
+    let slip = t * t * (3. - 2. * t);
+    force.force = Vec2::NEG_X * SLIP_ACCELERATION * slip * growth.mass(*size, *difficulty);
+}
@@ -1,34 +1,107 @@
 use bevy::{
     prelude::*,
     render::{
-        camera::RenderTarget,
+        camera::{RenderTarget, ScalingMode, Viewport},
         render_resource::{
             Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
         },
         view::RenderLayers,
     },
+    window::{PrimaryWindow, WindowResized},
 };
 use bevy_rapier2d::prelude::*;
+use rand::Rng;
 
-use crate::{player::Player, GameState, WINDOW_HEIGHT, WINDOW_WIDTH};
+use crate::{boulder::Boulder, player::Player, GameState, WINDOW_HEIGHT, WINDOW_WIDTH};
 
 pub struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_camera)
-            .add_systems(Update, move_camera);
+        app.init_resource::<CameraShake>()
+            .add_systems(Startup, spawn_camera)
+            .add_systems(Update, (move_camera, letterbox))
+            .add_systems(
+                OnTransition {
+                    from: GameState::Cleanup,
+                    to: GameState::InGame,
+                },
+                reset_camera,
+            );
     }
 }
 
 #[derive(Component)]
-struct MainCamera;
+pub struct MainCamera;
 
 #[derive(Component)]
 struct UICamera;
 
+/// The image the UI camera renders into.
+#[derive(Resource)]
+struct UiCanvas(Handle<Image>);
+
+/// Eases the main camera toward the player instead of snapping to them.
+#[derive(Component)]
+pub struct CameraFollow {
+    /// How quickly the camera catches up, per second. Higher is snappier.
+    pub smoothing: f32,
+    /// How far, in pixels, the player can move up or down from where the
+    /// camera wants them before it follows, so it doesn't bob along the slope.
+    pub vertical_band: f32,
+}
+
+impl Default for CameraFollow {
+    fn default() -> Self {
+        Self {
+            smoothing: 6.,
+            vertical_band: 40.,
+        }
+    }
+}
+
 pub const UI_LAYER: RenderLayers = RenderLayers::layer(9);
 
+/// Distance, in pixels, the player can drift before the camera follows, so
+/// sub-pixel jitter from the character controller doesn't shimmer the pixel art.
+const CAMERA_DEAD_ZONE: f32 = 1.5;
+
+/// Player speed, in pixels per second, below which the camera stays zoomed in...
+const ZOOM_MIN_SPEED: f32 = 60.;
+/// ...and at which it's zoomed all the way out.
+const ZOOM_MAX_SPEED: f32 = 150.;
+/// Projection scale when zoomed out for speed. Kept small so the pixel art
+/// doesn't get too soft.
+const MAX_ZOOM: f32 = 1.2;
+/// Room, in pixels, kept between the boulder's center and the edge of the
+/// screen; enough for the biggest boulder to stay fully in shot.
+const BOULDER_FRAME_MARGIN: f32 = 128.;
+/// Projection scale the camera will pull back to to keep the boulder in shot.
+/// Past this it's let roll out of frame rather than shrink the pixel art away.
+const MAX_BOULDER_ZOOM: f32 = 2.;
+/// How quickly the zoom eases toward its target, per second.
+const ZOOM_SMOOTHING: f32 = 1.5;
+
+/// Largest offset, in pixels, the camera is knocked away from the player at full trauma.
+const MAX_SHAKE_OFFSET: f32 = 12.;
+/// Trauma lost per second.
+const TRAUMA_DECAY: f32 = 1.5;
+
+/// Screen shake, where `trauma` goes from 0 (still) to 1 (violent). The offset
+/// scales with trauma squared so small bumps stay subtle.
+#[derive(Resource, Default)]
+pub struct CameraShake {
+    pub trauma: f32,
+    /// Offset applied last frame, taken back out before following the player.
+    offset: Vec2,
+}
+
+impl CameraShake {
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).min(1.);
+    }
+}
+
 fn spawn_camera(
     mut commands: Commands,
     mut images: ResMut<Assets<Image>>,
@@ -58,6 +131,7 @@ fn spawn_camera(
     };
     canvas.resize(canvas_size);
     let image_handle = images.add(canvas);
+    commands.insert_resource(UiCanvas(image_handle.clone()));
 
     commands.spawn((
         Camera2dBundle {
@@ -72,22 +146,157 @@ fn spawn_camera(
         UI_LAYER,
     ));
 
-    commands.spawn((Camera2dBundle::default(), MainCamera));
+    // Always show the same slice of the world, whatever the window size.
+    let mut main_camera = Camera2dBundle::default();
+    main_camera.projection.scaling_mode = ScalingMode::Fixed {
+        width: WINDOW_WIDTH,
+        height: WINDOW_HEIGHT,
+    };
+
+    commands.spawn((main_camera, MainCamera, CameraFollow::default()));
 
     next_state.set(GameState::MainMenu);
 }
 
+/// Cut straight back to the bottom of the hill for a retry, rather than
+/// panning all the way down after the freshly spawned player.
+fn reset_camera(
+    mut shake: ResMut<CameraShake>,
+    mut camera: Query<(&mut Transform, &mut OrthographicProjection), With<MainCamera>>,
+) {
+    if let Ok((mut transform, mut projection)) = camera.get_single_mut() {
+        transform.translation.x = 0.;
+        transform.translation.y = 0.;
+        projection.scale = 1.;
+    }
+    *shake = CameraShake::default();
+}
+
+/// Scale the game up by the largest whole number that fits the window, with
+/// black bars around it, so the pixel art stays crisp. The UI and its canvas
+/// are scaled to match.
+fn letterbox(
+    mut resized: EventReader<WindowResized>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut camera: Query<&mut Camera, With<MainCamera>>,
+    mut ui_scale: ResMut<UiScale>,
+    canvas: Res<UiCanvas>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    if resized.read().last().is_none() {
+        return;
+    }
+    let (Ok(window), Ok(mut camera)) = (window.get_single(), camera.get_single_mut()) else {
+        return;
+    };
+
+    let window_size = UVec2::new(window.physical_width(), window.physical_height());
+    let canvas_size = Vec2::new(WINDOW_WIDTH, WINDOW_HEIGHT);
+    let fit = window_size.as_vec2() / canvas_size;
+    let scale = fit.x.min(fit.y).floor();
+
+    if scale < 1. {
+        // Too small to scale by a whole number; squeeze it in as best we can.
+        camera.viewport = None;
+        ui_scale.0 = fit.x.min(fit.y) / window.scale_factor();
+        return;
+    }
+
+    let viewport_size = (canvas_size * scale).as_uvec2();
+    camera.viewport = Some(Viewport {
+        physical_position: (window_size - viewport_size) / 2,
+        physical_size: viewport_size,
+        ..default()
+    });
+    ui_scale.0 = scale / window.scale_factor();
+
+    if let Some(image) = images.get_mut(&canvas.0) {
+        image.resize(Extent3d {
+            width: viewport_size.x,
+            height: viewport_size.y,
+            ..default()
+        });
+    }
+}
+
+/// The camera's position, zoom and follow settings.
+type FollowCamera<'a> = (
+    &'a mut Transform,
+    &'a mut OrthographicProjection,
+    &'a CameraFollow,
+);
+
+/// The player's position and how far the controller actually moved them last step.
+type FollowTarget<'a> = (
+    &'a Transform,
+    Option<&'a KinematicCharacterControllerOutput>,
+);
+
 fn move_camera(
-    mut query: Query<(&mut Transform, &MainCamera), Without<Player>>,
-    player_query: Query<(&Transform, &Player), With<KinematicCharacterController>>,
+    time: Res<Time>,
+    fixed_time: Res<Time<Fixed>>,
+    mut shake: ResMut<CameraShake>,
+    mut query: Query<FollowCamera, (Without<Player>, Without<Boulder>)>,
+    player_query: Query<FollowTarget, (With<Player>, With<KinematicCharacterController>)>,
+    boulder_query: Query<&Transform, With<Boulder>>,
 ) {
-    if query.is_empty() || player_query.is_empty() {
+    let (Ok((mut camera, mut projection, follow)), Ok((transform, output))) =
+        (query.get_single_mut(), player_query.get_single())
+    else {
         return;
+    };
+
+    // Pull back a little on fast stretches so there's more to see coming.
+    if time.delta_seconds() > 0. && fixed_time.delta_seconds() > 0. {
+        // The controller output is what moved the player in the last fixed
+        // step, so it has to be divided by that step rather than the frame.
+        let speed = output.map_or(0., |output| {
+            output.effective_translation.x.abs() / fixed_time.delta_seconds()
+        });
+        let t = ((speed - ZOOM_MIN_SPEED) / (ZOOM_MAX_SPEED - ZOOM_MIN_SPEED)).clamp(0., 1.);
+        let speed_zoom = 1. + (MAX_ZOOM - 1.) * t;
+
+        // Pull back further when the boulder gets away, so it stays in shot.
+        let boulder_zoom = boulder_query.get_single().map_or(1., |boulder| {
+            let apart = (boulder.translation - camera.translation).truncate().abs()
+                + Vec2::splat(BOULDER_FRAME_MARGIN);
+            (apart / (Vec2::new(WINDOW_WIDTH, WINDOW_HEIGHT) / 2.)).max_element()
+        });
+
+        let target = speed_zoom.max(boulder_zoom).clamp(1., MAX_BOULDER_ZOOM);
+        projection.scale +=
+            (target - projection.scale) * (1. - (-ZOOM_SMOOTHING * time.delta_seconds()).exp());
+        projection.scale = projection.scale.clamp(1., MAX_BOULDER_ZOOM);
     }
 
-    let mut camera = query.single_mut();
-    let transform = player_query.single().0;
+    let position = camera.translation.truncate() - shake.offset;
+    // Only chase the player vertically once they leave the band, and then
+    // just far enough to bring them back to its edge.
+    let target_y = transform.translation.y + WINDOW_HEIGHT / 5.;
+    let offset_y = target_y - position.y;
+    let target = Vec2::new(
+        transform.translation.x,
+        position.y + offset_y.signum() * (offset_y.abs() - follow.vertical_band).max(0.),
+    );
+    let position = if position.distance(target) > CAMERA_DEAD_ZONE {
+        // Exponential decay so the ease feels the same at any frame rate.
+        position.lerp(
+            target,
+            1. - (-follow.smoothing * time.delta_seconds()).exp(),
+        )
+    } else {
+        position
+    };
+
+    shake.offset = Vec2::ZERO;
+    if shake.trauma > 0. {
+        let strength = shake.trauma * shake.trauma * MAX_SHAKE_OFFSET;
+        let mut rng = rand::thread_rng();
+        shake.offset = Vec2::new(rng.gen_range(-1.0..=1.0), rng.gen_range(-1.0..=1.0)) * strength;
+
+        shake.trauma = (shake.trauma - TRAUMA_DECAY * time.delta_seconds()).max(0.);
+    }
 
-    camera.0.translation.x = transform.translation.x;
-    camera.0.translation.y = transform.translation.y + WINDOW_HEIGHT / 5.;
+    camera.translation.x = position.x + shake.offset.x;
+    camera.translation.y = position.y + shake.offset.y;
 }
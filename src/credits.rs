@@ -0,0 +1,153 @@
+use bevy::prelude::*;
+
+use crate::{camera::UI_LAYER, input::GamepadInput, GameState};
+
+pub struct CreditsPlugin;
+
+impl Plugin for CreditsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Credits), setup_credits_menu)
+            .add_systems(
+                Update,
+                credits_menu_system.run_if(in_state(GameState::Credits)),
+            )
+            .add_systems(OnExit(GameState::Credits), cleanup_credits_menu);
+    }
+}
+
+/// What each asset is used for, and who made it. Kept in step with the README.
+const CREDITS: [(&str, &str); 7] = [
+    ("Music", "joshuuu"),
+    ("Boulder texture", "Screaming Brain Studios"),
+    ("Player sprites", "zegley"),
+    ("Title font", "GGBotNet"),
+    ("Button font", "emhuo"),
+    ("Fatigue meter", "bdragon1727"),
+    ("Space background", "Screaming Brain Studios"),
+];
+
+fn setup_credits_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let title_font: Handle<Font> = asset_server.load("fonts/Kaph-Regular.ttf");
+    let font = asset_server.load("fonts/PeaberryMono.ttf");
+
+    let text_style = TextStyle {
+        color: Color::WHITE,
+        font_size: 25.0,
+        font,
+    };
+
+    let credits = CREDITS
+        .iter()
+        .map(|(what, who)| format!("{what}: {who}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            UI_LAYER,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "Credits".to_string(),
+                    TextStyle {
+                        font_size: 60.0,
+                        color: Color::WHITE,
+                        font: title_font,
+                    },
+                ),
+                UI_LAYER,
+            ));
+
+            parent.spawn((
+                TextBundle::from_section(credits, text_style.clone())
+                    .with_text_justify(JustifyText::Center)
+                    .with_style(Style {
+                        margin: UiRect {
+                            top: Val::Px(20.),
+                            ..default()
+                        },
+                        ..default()
+                    }),
+                UI_LAYER,
+            ));
+
+            parent
+                .spawn(ButtonBundle {
+                    background_color: Color::PURPLE.into(),
+                    style: Style {
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        width: Val::Px(150.),
+                        height: Val::Px(50.),
+                        margin: UiRect {
+                            top: Val::Px(20.),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Back".to_string(),
+                        text_style.clone(),
+                    ));
+                });
+        });
+}
+
+/// Buttons whose `Interaction` changed this frame.
+type ChangedButton = (Changed<Interaction>, With<Button>);
+
+fn credits_menu_system(
+    mut state: ResMut<NextState<GameState>>,
+    gamepad: GamepadInput,
+    interaction_query: Query<(&Interaction, &Children), ChangedButton>,
+    mut text_query: Query<&mut Text>,
+) {
+    if gamepad.just_pressed(GamepadButtonType::East) {
+        state.set(GameState::MainMenu);
+    }
+
+    for (interaction, children) in &interaction_query {
+        let mut text = text_query.get_mut(children[0]).unwrap();
+        match *interaction {
+            Interaction::Pressed => {
+                if text.sections[0].value == "Back" {
+                    state.set(GameState::MainMenu);
+                }
+            }
+            Interaction::Hovered => {
+                text.sections[0].style.font_size = 30.0;
+            }
+            Interaction::None => {
+                text.sections[0].style.font_size = 25.0;
+            }
+        }
+    }
+}
+
+fn cleanup_credits_menu(
+    mut commands: Commands,
+    interaction_query: Query<Entity, With<Button>>,
+    text_query: Query<Entity, With<Text>>,
+) {
+    for entity in &text_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    for entity in &interaction_query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+use bevy_pkv::PkvStore;
+use serde::{Deserialize, Serialize};
+
+use crate::GameState;
+
+/// PkvStore key for the last chosen `Difficulty`.
+const DIFFICULTY_KEY: &str = "difficulty";
+
+pub struct DifficultyPlugin;
+
+impl Plugin for DifficultyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_difficulty).add_systems(
+            Update,
+            difficulty_button_system.run_if(in_state(GameState::MainMenu)),
+        );
+    }
+}
+
+/// How hard the climb is, chosen on the main menu.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    fn next(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Easy,
+        }
+    }
+
+    /// The text for the main menu button.
+    pub fn label(self) -> String {
+        format!("Difficulty: {self:?}")
+    }
+
+    /// Multiplier on how fast pushing tires the player out.
+    pub fn fatigue_scale(self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.6,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.5,
+        }
+    }
+
+    /// Multiplier on the player's walking and pushing speed.
+    pub fn speed_scale(self) -> f32 {
+        match self {
+            Difficulty::Easy => 1.2,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 0.9,
+        }
+    }
+
+    /// Multiplier on the boulder's mass.
+    pub fn mass_scale(self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.75,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.25,
+        }
+    }
+
+    /// Multiplier on the angle of the generated slopes.
+    pub fn slope_scale(self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.75,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.3,
+        }
+    }
+}
+
+/// The main menu button that cycles through the difficulties.
+#[derive(Component)]
+pub struct DifficultyButton;
+
+fn load_difficulty(mut commands: Commands, pkv: Res<PkvStore>) {
+    let difficulty = pkv.get::<Difficulty>(DIFFICULTY_KEY).unwrap_or_default();
+    commands.insert_resource(difficulty);
+}
+
+/// The difficulty button, if its `Interaction` changed this frame.
+type ChangedButton = (Changed<Interaction>, With<DifficultyButton>);
+
+fn difficulty_button_system(
+    mut difficulty: ResMut<Difficulty>,
+    mut pkv: ResMut<PkvStore>,
+    interaction_query: Query<(&Interaction, &Children), ChangedButton>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (interaction, children) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        *difficulty = difficulty.next();
+        if let Err(err) = pkv.set(DIFFICULTY_KEY, &*difficulty) {
+            warn!("failed to save difficulty: {err}");
+        }
+
+        if let Ok(mut text) = text_query.get_mut(children[0]) {
+            text.sections[0].value = difficulty.label();
+        }
+    }
+}
@@ -1,109 +1,239 @@
-use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::{Indices, PrimitiveTopology},
+        render_asset::RenderAssetUsages,
+    },
+    sprite::MaterialMesh2dBundle,
+};
 use bevy_rapier2d::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
-use crate::{player::Player, GameState, WINDOW_BOTTOM_Y, WINDOW_HEIGHT, WINDOW_WIDTH};
+use crate::{difficulty::Difficulty, player::Player, GameState, WINDOW_BOTTOM_Y, WINDOW_HEIGHT};
 
 const COLOR_FLOOR: Color = Color::DARK_GREEN;
 
+/// Horizontal length of each straight stretch of slope.
+const SEGMENT_WIDTH: f32 = 256.;
+/// Stretches of slope in each ground chunk.
+const CHUNK_SEGMENTS: usize = 8;
+const CHUNK_WIDTH: f32 = SEGMENT_WIDTH * CHUNK_SEGMENTS as f32;
+/// Chunks kept loaded behind and ahead of the one the player is on.
+const CHUNKS_BEHIND: usize = 1;
+const CHUNKS_AHEAD: usize = 2;
+/// Left end of the ground, a little behind the wall.
+const GROUND_START_X: f32 = -3. * SEGMENT_WIDTH;
+/// Stretches at `START_SLOPE` before the generated slopes begin.
+const START_SEGMENTS: usize = 4;
+/// Slope of the starting stretches, in degrees.
+const START_SLOPE: f32 = 7.5;
+/// Gentlest and steepest generated slopes, in degrees.
+const MIN_SLOPE: f32 = 5.;
+const MAX_SLOPE: f32 = 20.;
+/// Stretches over which the steepest possible slope ramps up to `MAX_SLOPE`.
+const RAMP_SEGMENTS: f32 = 120.;
+/// Stretches between the random control points of the slope noise. Longer
+/// periods give longer, gentler swells between steep and shallow ground.
+const NOISE_PERIOD: usize = 6;
+
+/// How far the visible ground sits below the collider, so feet sink into the grass.
+const FOREGROUND_OVERLAP: f32 = 12.;
+/// How far the visible ground reaches down below its surface.
+const FOREGROUND_DEPTH: f32 = WINDOW_HEIGHT;
+
 pub struct GroundPlugin;
 
-#[derive(Component)]
-struct Ground;
+/// Where a ball of `radius` rests on the starting stretch of slope, centered
+/// over `x`. The starting stretch is the same whatever the seed or difficulty.
+pub fn rest_on_start_slope(x: f32, radius: f32) -> Vec2 {
+    let angle = START_SLOPE.to_radians();
+    let surface = WINDOW_BOTTOM_Y + x * angle.tan();
+    // Touching the slope along its normal, which sits higher than straight down.
+    Vec2::new(x, surface + radius / angle.cos())
+}
 
-#[derive(Component)]
-struct Foreground;
+/// Seed for the slope generator. Insert a known value to replay a run's terrain.
+#[derive(Resource)]
+pub struct GroundSeed(pub u64);
 
-impl Plugin for GroundPlugin {
-    fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::InGame), (spawn_foreground, spawn_ground))
-            .add_systems(
-                FixedUpdate,
-                (expand, keep_centered).run_if(in_state(GameState::InGame)),
-            );
+impl Default for GroundSeed {
+    fn default() -> Self {
+        Self(rand::random())
     }
 }
 
-fn spawn_foreground(mut commands: Commands, ground_query: Query<&Ground>) {
-    if !ground_query.is_empty() {
-        return;
+/// One stretch of the ground, both collider and visible mesh.
+#[derive(Component)]
+struct GroundChunk {
+    index: usize,
+}
+
+/// The surface of the slope, left to right, generated as far as it's been needed.
+#[derive(Resource)]
+struct GroundProfile {
+    rng: StdRng,
+    /// Noise control points, from 0 to 1, one every `NOISE_PERIOD` stretches.
+    lattice: Vec<f32>,
+    points: Vec<Vec2>,
+    /// Multiplier on the generated slopes, from the difficulty.
+    slope_scale: f32,
+}
+
+impl GroundProfile {
+    fn new(seed: u64, slope_scale: f32) -> Self {
+        // Start out on the old fixed slope so the wall, player and boulder
+        // still spawn where they used to.
+        let start_slope = START_SLOPE.to_radians().tan();
+        let start = Vec2::new(
+            GROUND_START_X,
+            WINDOW_BOTTOM_Y + GROUND_START_X * start_slope,
+        );
+
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            lattice: Vec::new(),
+            points: vec![start],
+            slope_scale,
+        }
     }
 
-    // Slope
-    commands
-        .spawn(SpriteBundle {
-            sprite: Sprite {
-                color: COLOR_FLOOR,
-                ..default()
-            },
-            transform: Transform {
-                translation: Vec3::new(0., WINDOW_BOTTOM_Y - WINDOW_HEIGHT / 4. - 12., 6.),
-                scale: Vec3::new(WINDOW_WIDTH * 2., WINDOW_HEIGHT / 2., 1.),
-                rotation: Quat::from_rotation_z(7.5_f32.to_radians()),
-            },
-            ..default()
-        })
-        .insert(Foreground);
+    /// Smooth 1D value noise from 0 to 1, eased between the control points.
+    fn noise(&mut self, segment: usize) -> f32 {
+        let cell = segment / NOISE_PERIOD;
+        while self.lattice.len() <= cell + 1 {
+            let value = self.rng.gen();
+            self.lattice.push(value);
+        }
+
+        let t = (segment % NOISE_PERIOD) as f32 / NOISE_PERIOD as f32;
+        let t = t * t * (3. - 2. * t);
+        self.lattice[cell] + (self.lattice[cell + 1] - self.lattice[cell]) * t
+    }
+
+    /// The surface points of a chunk, including the one it shares with the
+    /// next. The slope gets steeper, on average, the further up the hill it goes.
+    fn chunk(&mut self, index: usize) -> &[Vec2] {
+        let first = index * CHUNK_SEGMENTS;
+        let last = first + CHUNK_SEGMENTS;
+
+        while self.points.len() <= last {
+            let segment = self.points.len() - 1;
+            let angle = if segment < START_SEGMENTS {
+                START_SLOPE
+            } else {
+                let progress = ((segment - START_SEGMENTS) as f32 / RAMP_SEGMENTS).min(1.);
+                let steepest = START_SLOPE + (MAX_SLOPE - START_SLOPE) * progress;
+                let angle =
+                    MIN_SLOPE + (steepest - MIN_SLOPE) * self.noise(segment - START_SEGMENTS);
+                angle * self.slope_scale
+            };
+
+            let end = *self.points.last().unwrap();
+            let rise = SEGMENT_WIDTH * angle.to_radians().tan();
+            self.points.push(end + Vec2::new(SEGMENT_WIDTH, rise));
+        }
+
+        &self.points[first..=last]
+    }
 }
 
-fn spawn_ground(
+impl Plugin for GroundPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GroundSeed>().add_systems(
+            Update,
+            (
+                build_ground_profile.run_if(resource_changed::<Difficulty>),
+                // Also behind the main menu, so it shows the hill rather than an empty sky.
+                update_chunks
+                    .run_if(in_state(GameState::MainMenu).or_else(in_state(GameState::InGame))),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// (Re)generate the hill for the chosen difficulty, keeping the same seed so
+/// only the steepness changes. The old chunks make way for new ones.
+fn build_ground_profile(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
+    seed: Res<GroundSeed>,
+    difficulty: Res<Difficulty>,
+    chunks: Query<Entity, With<GroundChunk>>,
 ) {
-    commands
-        .spawn(MaterialMesh2dBundle {
-            mesh: meshes
-                .add(Plane3d {
-                    normal: Direction3d::Y,
-                })
-                .into(),
-            material: materials.add(Color::DARK_GREEN),
-            transform: Transform {
-                translation: Vec3::new(0., WINDOW_BOTTOM_Y, 0.),
-                rotation: Quat::from_rotation_z(7.5_f32.to_radians()),
-                ..default()
-            },
-            ..default()
-        })
-        .insert(Ground)
-        .insert(RigidBody::Fixed)
-        .insert(Friction::coefficient(0.7))
-        .insert(Damping {
-            linear_damping: 0.7,
-            angular_damping: 0.7,
-        })
-        .insert(Collider::cuboid(WINDOW_WIDTH, 1.));
+    info!("ground seed: {}", seed.0);
+    commands.insert_resource(GroundProfile::new(seed.0, difficulty.slope_scale()));
+    for entity in &chunks {
+        commands.entity(entity).despawn_recursive();
+    }
 }
 
-fn expand(
-    mut query: Query<&mut Transform, With<Ground>>,
-    player_query: Query<&KinematicCharacterControllerOutput, With<crate::player::Player>>,
-) {
-    if player_query.is_empty() || query.is_empty() {
-        return;
+/// A strip hanging down from the slope's surface.
+fn foreground_mesh(points: &[Vec2]) -> Mesh {
+    let mut positions = Vec::with_capacity(points.len() * 2);
+    for point in points {
+        let top = point.y - FOREGROUND_OVERLAP;
+        positions.push([point.x, top, 0.]);
+        positions.push([point.x, top - FOREGROUND_DEPTH, 0.]);
     }
-    let player = player_query.single();
 
-    for mut ground in &mut query {
-        ground.scale.x += player.effective_translation.x.abs();
+    let mut indices = Vec::with_capacity((points.len() - 1) * 6);
+    for i in 0..points.len() as u32 - 1 {
+        let (top, bottom) = (i * 2, i * 2 + 1);
+        let (next_top, next_bottom) = (top + 2, bottom + 2);
+        indices.extend([top, bottom, next_bottom, top, next_bottom, next_top]);
     }
+
+    let vertex_count = positions.len();
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0., 0., 1.]; vertex_count])
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, vec![[0., 0.]; vertex_count])
+    .with_inserted_indices(Indices::U32(indices))
 }
 
-fn keep_centered(
-    mut query: Query<(&mut Transform, &Foreground), Without<Player>>,
-    player_query: Query<
-        (&Transform, &KinematicCharacterControllerOutput),
-        With<crate::player::Player>,
-    >,
+/// Keep a window of chunks around the player, spawning them ahead and
+/// despawning them once they're well out of sight behind. Before the player
+/// is spawned, the window sits at the bottom of the hill.
+fn update_chunks(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut profile: ResMut<GroundProfile>,
+    player: Query<&Transform, With<Player>>,
+    chunks: Query<(Entity, &GroundChunk)>,
 ) {
-    if player_query.is_empty() || query.is_empty() {
-        return;
+    let player_x = player
+        .get_single()
+        .map_or(0., |player| player.translation.x);
+    let current = ((player_x - GROUND_START_X) / CHUNK_WIDTH).max(0.) as usize;
+    let window = current.saturating_sub(CHUNKS_BEHIND)..=current + CHUNKS_AHEAD;
+
+    for (entity, chunk) in &chunks {
+        if !window.contains(&chunk.index) {
+            commands.entity(entity).despawn_recursive();
+        }
     }
-    let (player, output) = player_query.single();
-    let (mut ground, _) = query.single_mut();
 
-    ground.translation.x = player.translation.x;
-    if output.grounded {
-        ground.translation.y = player.translation.y - WINDOW_HEIGHT / 4. - 24.;
+    for index in window {
+        if chunks.iter().any(|(_, chunk)| chunk.index == index) {
+            continue;
+        }
+
+        let points = profile.chunk(index).to_vec();
+        commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: meshes.add(foreground_mesh(&points)).into(),
+                material: materials.add(COLOR_FLOOR),
+                transform: Transform::from_xyz(0., 0., 6.),
+                ..default()
+            },
+            GroundChunk { index },
+            RigidBody::Fixed,
+            Friction::coefficient(0.7),
+            Collider::polyline(points, None),
+        ));
     }
 }
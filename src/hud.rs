@@ -0,0 +1,160 @@
+use bevy::prelude::*;
+
+use crate::{
+    camera::UI_LAYER,
+    format_time,
+    player::{Fatigue, Player},
+    DistanceTraveled, GameState, RunTime,
+};
+
+pub struct HudPlugin;
+
+#[derive(Component)]
+struct Hud;
+
+#[derive(Component)]
+struct DistanceText;
+
+#[derive(Component)]
+struct RunTimeText;
+
+/// The colored part of the fatigue bar, sized to the player's fatigue.
+#[derive(Component)]
+struct FatigueFill;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::InGame), spawn_hud)
+            .add_systems(
+                Update,
+                (
+                    update_distance_text,
+                    update_run_time_text,
+                    update_fatigue_bar,
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(OnExit(GameState::InGame), despawn_hud);
+    }
+}
+
+fn spawn_hud(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/PeaberryMono.ttf");
+
+    let text_style = TextStyle {
+        color: Color::WHITE,
+        font_size: 25.0,
+        font,
+    };
+
+    // Top-center, clear of the fatigue bar in the corner. The HUD is
+    // torn down while paused since the pause menu clears every `Text` on exit.
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::FlexStart,
+                    padding: UiRect::all(Val::Px(10.)),
+                    ..default()
+                },
+                ..default()
+            },
+            UI_LAYER,
+            Hud,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section("0 m".to_string(), text_style.clone()),
+                UI_LAYER,
+                DistanceText,
+            ));
+
+            parent.spawn((
+                TextBundle::from_section("00:00".to_string(), text_style).with_style(Style {
+                    margin: UiRect {
+                        left: Val::Px(20.),
+                        ..default()
+                    },
+                    ..default()
+                }),
+                UI_LAYER,
+                RunTimeText,
+            ));
+
+            // Pinned to the top-left corner so it doesn't move with the player.
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            left: Val::Px(10.),
+                            top: Val::Px(10.),
+                            width: Val::Px(100.),
+                            height: Val::Px(12.),
+                            border: UiRect::all(Val::Px(2.)),
+                            ..default()
+                        },
+                        background_color: Color::rgb(0.1, 0.1, 0.1).into(),
+                        border_color: Color::WHITE.into(),
+                        ..default()
+                    },
+                    UI_LAYER,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(0.),
+                                height: Val::Percent(100.),
+                                ..default()
+                            },
+                            background_color: Color::GREEN.into(),
+                            ..default()
+                        },
+                        UI_LAYER,
+                        FatigueFill,
+                    ));
+                });
+        });
+}
+
+fn update_distance_text(
+    distance_traveled: Res<DistanceTraveled>,
+    mut query: Query<&mut Text, With<DistanceText>>,
+) {
+    for mut text in &mut query {
+        text.sections[0].value = format!("{:.0} m", distance_traveled.0 / 64.);
+    }
+}
+
+fn update_run_time_text(run_time: Res<RunTime>, mut query: Query<&mut Text, With<RunTimeText>>) {
+    for mut text in &mut query {
+        text.sections[0].value = format_time(run_time.0);
+    }
+}
+
+fn update_fatigue_bar(
+    player: Query<&Fatigue, With<Player>>,
+    mut query: Query<(&mut Style, &mut BackgroundColor), With<FatigueFill>>,
+) {
+    let Ok(fatigue) = player.get_single() else {
+        return;
+    };
+    let fatigue = fatigue.0.clamp(0., 100.);
+
+    for (mut style, mut color) in &mut query {
+        style.width = Val::Percent(fatigue);
+        // Green when fresh, red when about to collapse.
+        let t = fatigue / 100.;
+        color.0 = Color::rgb(t, 1. - t, 0.);
+    }
+}
+
+fn despawn_hud(mut commands: Commands, query: Query<Entity, With<Hud>>) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
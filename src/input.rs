@@ -0,0 +1,419 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_pkv::PkvStore;
+use serde::{Deserialize, Serialize};
+
+use crate::{camera::UI_LAYER, GameState};
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Rebinding>()
+            .add_systems(Startup, load_key_bindings)
+            .add_systems(OnEnter(GameState::Controls), setup_controls_menu)
+            .add_systems(
+                Update,
+                (controls_menu_system, rebind_key).run_if(in_state(GameState::Controls)),
+            )
+            .add_systems(OnExit(GameState::Controls), cleanup_controls_menu);
+    }
+}
+
+/// PkvStore key for the player's `KeyBindings`.
+const KEY_BINDINGS_KEY: &str = "key_bindings";
+
+/// Fields missing from an older save fall back to their defaults.
+#[derive(Resource, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub move_left: KeyCode,
+    pub move_right: KeyCode,
+    /// Second keys for moving, so WASD works as well as the arrows.
+    pub move_left_alt: KeyCode,
+    pub move_right_alt: KeyCode,
+    pub rest: KeyCode,
+    pub jump: KeyCode,
+    pub pause: KeyCode,
+    pub confirm: KeyCode,
+    pub volume_up: KeyCode,
+    pub volume_down: KeyCode,
+    pub mute: KeyCode,
+    pub fullscreen: KeyCode,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            move_left: KeyCode::ArrowLeft,
+            move_right: KeyCode::ArrowRight,
+            move_left_alt: KeyCode::KeyA,
+            move_right_alt: KeyCode::KeyD,
+            rest: KeyCode::ArrowDown,
+            jump: KeyCode::Space,
+            pause: KeyCode::Escape,
+            confirm: KeyCode::Space,
+            volume_up: KeyCode::Equal,
+            volume_down: KeyCode::Minus,
+            mute: KeyCode::Digit0,
+            fullscreen: KeyCode::F11,
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn left_pressed(&self, keyboard_input: &ButtonInput<KeyCode>) -> bool {
+        keyboard_input.any_pressed([self.move_left, self.move_left_alt])
+    }
+
+    pub fn right_pressed(&self, keyboard_input: &ButtonInput<KeyCode>) -> bool {
+        keyboard_input.any_pressed([self.move_right, self.move_right_alt])
+    }
+
+    fn key(&self, action: Action) -> KeyCode {
+        match action {
+            Action::MoveLeft => self.move_left,
+            Action::MoveRight => self.move_right,
+            Action::Rest => self.rest,
+            Action::Jump => self.jump,
+            Action::Pause => self.pause,
+            Action::Confirm => self.confirm,
+            Action::VolumeUp => self.volume_up,
+            Action::VolumeDown => self.volume_down,
+            Action::Mute => self.mute,
+            Action::Fullscreen => self.fullscreen,
+        }
+    }
+
+    fn key_mut(&mut self, action: Action) -> &mut KeyCode {
+        match action {
+            Action::MoveLeft => &mut self.move_left,
+            Action::MoveRight => &mut self.move_right,
+            Action::Rest => &mut self.rest,
+            Action::Jump => &mut self.jump,
+            Action::Pause => &mut self.pause,
+            Action::Confirm => &mut self.confirm,
+            Action::VolumeUp => &mut self.volume_up,
+            Action::VolumeDown => &mut self.volume_down,
+            Action::Mute => &mut self.mute,
+            Action::Fullscreen => &mut self.fullscreen,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Action {
+    MoveLeft,
+    MoveRight,
+    Rest,
+    Jump,
+    Pause,
+    Confirm,
+    VolumeUp,
+    VolumeDown,
+    Mute,
+    Fullscreen,
+}
+
+impl Action {
+    const ALL: [Action; 10] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Rest,
+        Action::Jump,
+        Action::Pause,
+        Action::Confirm,
+        Action::VolumeUp,
+        Action::VolumeDown,
+        Action::Mute,
+        Action::Fullscreen,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::Rest => "Rest",
+            Action::Jump => "Jump",
+            Action::Pause => "Pause",
+            Action::Confirm => "Confirm",
+            Action::VolumeUp => "Music up",
+            Action::VolumeDown => "Music down",
+            Action::Mute => "Mute",
+            Action::Fullscreen => "Fullscreen",
+        }
+    }
+}
+
+/// The action waiting on a key press after its row was clicked.
+#[derive(Resource, Default)]
+struct Rebinding(Option<Action>);
+
+/// A button on the controls screen that rebinds `Action`.
+#[derive(Component)]
+struct BindingRow(Action);
+
+/// A short, readable name for a key, like "-" for `KeyCode::Minus`.
+pub fn key_label(key: KeyCode) -> String {
+    match key {
+        KeyCode::Minus => "-".to_string(),
+        KeyCode::Equal => "=".to_string(),
+        KeyCode::ArrowLeft => "Left".to_string(),
+        KeyCode::ArrowRight => "Right".to_string(),
+        KeyCode::ArrowUp => "Up".to_string(),
+        KeyCode::ArrowDown => "Down".to_string(),
+        _ => {
+            let name = format!("{key:?}");
+            match name.strip_prefix("Key").or(name.strip_prefix("Digit")) {
+                Some(short) => short.to_string(),
+                None => name,
+            }
+        }
+    }
+}
+
+/// The menu text showing `volume_hint`, kept up to date as the mute is toggled.
+#[derive(Component)]
+pub struct VolumeHint;
+
+/// The menu hint for the volume keys. Effects are only set from the settings menu.
+pub fn volume_hint(bindings: &KeyBindings, muted: bool) -> String {
+    format!(
+        "{}/{} to lower/raise music\n{} to {}",
+        key_label(bindings.volume_down),
+        key_label(bindings.volume_up),
+        key_label(bindings.mute),
+        if muted { "unmute" } else { "mute" },
+    )
+}
+
+fn binding_label(action: Action, bindings: &KeyBindings) -> String {
+    let alt = match action {
+        Action::MoveLeft => Some(bindings.move_left_alt),
+        Action::MoveRight => Some(bindings.move_right_alt),
+        _ => None,
+    };
+    let key = key_label(bindings.key(action));
+    match alt {
+        Some(alt) => format!("{}: {} / {}", action.label(), key, key_label(alt)),
+        None => format!("{}: {}", action.label(), key),
+    }
+}
+
+fn load_key_bindings(mut commands: Commands, pkv: Res<PkvStore>) {
+    let bindings = pkv.get::<KeyBindings>(KEY_BINDINGS_KEY).unwrap_or_default();
+    commands.insert_resource(bindings);
+}
+
+/// Left stick deflection below which the stick counts as centered, so a worn
+/// controller resting slightly off-center doesn't walk the player.
+const STICK_DEAD_ZONE: f32 = 0.25;
+
+/// Buttons and sticks across every connected gamepad, read alongside the keyboard.
+#[derive(SystemParam)]
+pub struct GamepadInput<'w> {
+    gamepads: Res<'w, Gamepads>,
+    buttons: Res<'w, ButtonInput<GamepadButton>>,
+    axes: Res<'w, Axis<GamepadAxis>>,
+}
+
+impl GamepadInput<'_> {
+    pub fn pressed(&self, button_type: GamepadButtonType) -> bool {
+        self.gamepads.iter().any(|gamepad| {
+            self.buttons
+                .pressed(GamepadButton::new(gamepad, button_type))
+        })
+    }
+
+    pub fn just_pressed(&self, button_type: GamepadButtonType) -> bool {
+        self.gamepads.iter().any(|gamepad| {
+            self.buttons
+                .just_pressed(GamepadButton::new(gamepad, button_type))
+        })
+    }
+
+    /// Horizontal left stick position from -1 to 1, or 0 inside the dead-zone.
+    pub fn left_stick_x(&self) -> f32 {
+        self.gamepads
+            .iter()
+            .filter_map(|gamepad| {
+                self.axes
+                    .get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX))
+            })
+            .filter(|x| x.abs() >= STICK_DEAD_ZONE)
+            .max_by(|a, b| a.abs().total_cmp(&b.abs()))
+            .unwrap_or(0.)
+    }
+}
+
+fn setup_controls_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    bindings: Res<KeyBindings>,
+) {
+    let title_font: Handle<Font> = asset_server.load("fonts/Kaph-Regular.ttf");
+    let font = asset_server.load("fonts/PeaberryMono.ttf");
+
+    let text_style = TextStyle {
+        color: Color::WHITE,
+        font_size: 25.0,
+        font,
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            UI_LAYER,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "Controls".to_string(),
+                    TextStyle {
+                        font_size: 60.0,
+                        color: Color::WHITE,
+                        font: title_font,
+                    },
+                ),
+                UI_LAYER,
+            ));
+
+            for action in Action::ALL {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            background_color: Color::PURPLE.into(),
+                            style: Style {
+                                align_items: AlignItems::Center,
+                                justify_content: JustifyContent::Center,
+                                width: Val::Px(360.),
+                                height: Val::Px(28.),
+                                margin: UiRect {
+                                    top: Val::Px(4.),
+                                    ..default()
+                                },
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        BindingRow(action),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            binding_label(action, &bindings),
+                            text_style.clone(),
+                        ));
+                    });
+            }
+
+            parent
+                .spawn(ButtonBundle {
+                    background_color: Color::PURPLE.into(),
+                    style: Style {
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        width: Val::Px(150.),
+                        height: Val::Px(50.),
+                        margin: UiRect {
+                            top: Val::Px(20.),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Back".to_string(),
+                        text_style.clone(),
+                    ));
+                });
+        });
+}
+
+/// Buttons on the controls screen whose `Interaction` changed this frame.
+type ControlsButton<'a> = (&'a Interaction, &'a Children, Option<&'a BindingRow>);
+
+fn controls_menu_system(
+    mut state: ResMut<NextState<GameState>>,
+    mut rebinding: ResMut<Rebinding>,
+    gamepad: GamepadInput,
+    interaction_query: Query<ControlsButton, (Changed<Interaction>, With<Button>)>,
+    mut text_query: Query<&mut Text>,
+) {
+    if gamepad.just_pressed(GamepadButtonType::East) {
+        state.set(GameState::MainMenu);
+    }
+
+    for (interaction, children, row) in &interaction_query {
+        let mut text = text_query.get_mut(children[0]).unwrap();
+        match *interaction {
+            Interaction::Pressed => {
+                if let Some(BindingRow(action)) = row {
+                    rebinding.0 = Some(*action);
+                    text.sections[0].value = format!("{}: press a key", action.label());
+                } else if text.sections[0].value == "Back" {
+                    state.set(GameState::MainMenu);
+                }
+            }
+            Interaction::Hovered => {
+                text.sections[0].style.font_size = 30.0;
+            }
+            Interaction::None => {
+                text.sections[0].style.font_size = 25.0;
+            }
+        }
+    }
+}
+
+fn rebind_key(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<KeyBindings>,
+    mut pkv: ResMut<PkvStore>,
+    rows: Query<(&BindingRow, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    let Some(action) = rebinding.0 else {
+        return;
+    };
+    let Some(&key) = keyboard_input.get_just_pressed().next() else {
+        return;
+    };
+
+    *bindings.key_mut(action) = key;
+    rebinding.0 = None;
+    if let Err(err) = pkv.set(KEY_BINDINGS_KEY, &*bindings) {
+        warn!("failed to save key bindings: {err}");
+    }
+
+    for (row, children) in &rows {
+        if let Ok(mut text) = text_query.get_mut(children[0]) {
+            text.sections[0].value = binding_label(row.0, &bindings);
+        }
+    }
+}
+
+fn cleanup_controls_menu(
+    mut commands: Commands,
+    mut rebinding: ResMut<Rebinding>,
+    interaction_query: Query<Entity, With<Button>>,
+    text_query: Query<Entity, With<Text>>,
+) {
+    rebinding.0 = None;
+    for entity in &text_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    for entity in &interaction_query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
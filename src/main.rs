@@ -1,8 +1,20 @@
 mod animation;
 mod boulder;
 mod camera;
+mod credits;
+mod difficulty;
 mod ground;
+mod hud;
+mod input;
+mod milestone;
+mod obstacle;
+mod particles;
 mod player;
+mod quit;
+mod settings;
+mod stats;
+mod touch;
+mod tutorial;
 
 use bevy::asset::AssetMetaCheck;
 use bevy::audio::{PlaybackMode, Volume};
@@ -17,11 +29,25 @@ use bevy_rapier2d::prelude::*;
 use rand::seq::SliceRandom;
 
 use animation::AnimationPlugin;
-use boulder::BoulderPlugin;
-use camera::{CameraPlugin, UI_LAYER};
+use boulder::{Boulder, BoulderPlugin, BoulderSize, BoulderSizeButton};
+use camera::{CameraPlugin, MainCamera, UI_LAYER};
+use credits::CreditsPlugin;
+use difficulty::{Difficulty, DifficultyButton, DifficultyPlugin};
 use ground::GroundPlugin;
-use player::PlayerPlugin;
+use hud::HudPlugin;
+use input::{volume_hint, ControlsPlugin, GamepadInput, KeyBindings, VolumeHint};
+use milestone::{LastMilestone, MilestonePlugin};
+use obstacle::{Obstacle, ObstaclePlugin};
+use particles::ParticlePlugin;
+use player::{Fatigue, Player, PlayerPlugin};
+use quit::{quit_confirm_open, QuitPlugin, QuitRequested};
+use settings::{MusicFade, SettingsPlugin, VolumeControl, VolumeKind, Volumes};
+use stats::StatsPlugin;
+use touch::{TouchButton, TouchButtons, TouchPlugin};
+use tutorial::TutorialPlugin;
 
+/// The game's name, as shown on the title screen and in the title bar.
+const GAME_TITLE: &str = "Sisyphus Simulator";
 pub const WINDOW_WIDTH: f32 = 640.;
 pub const WINDOW_HEIGHT: f32 = 480.;
 const WINDOW_BOTTOM_Y: f32 = WINDOW_HEIGHT / -2.;
@@ -39,11 +65,52 @@ struct SoundFX;
 #[derive(Resource)]
 struct DistanceTraveled(f32);
 
+/// Seconds spent in play this run, not counting pauses.
+#[derive(Resource)]
+struct RunTime(f32);
+
+/// `seconds` as minutes and seconds, like "02:07".
+fn format_time(seconds: f32) -> String {
+    let seconds = seconds as u32;
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Distance in meters at which the boulder reaches the summit and the run is won.
+#[derive(Resource)]
+struct SummitDistance(f32);
+
+/// PkvStore key for the furthest distance reached, in meters.
+const BEST_DISTANCE_KEY: &str = "best_distance";
+
+/// PkvStore key for the fastest time to the summit, in seconds.
+const BEST_TIME_KEY: &str = "best_time";
+
+/// PkvStore key for the music volume, from 0 to 1. While muted this is the
+/// volume to go back to.
+const VOLUME_KEY: &str = "volume";
+/// PkvStore key for the sound effects volume, from 0 to 1.
+const EFFECTS_VOLUME_KEY: &str = "effects_volume";
+const DEFAULT_VOLUME: f32 = 0.25;
+/// PkvStore key for whether the game is muted.
+const MUTED_KEY: &str = "muted";
+
+/// Fatigue above which the music starts to pick up.
+const CALM_FATIGUE: f32 = 40.;
+/// How quickly the music follows the player's fatigue, in full changes per second.
+const MUSIC_FADE_RATE: f32 = 0.5;
+/// How much faster, and higher, the music plays when the player is about to collapse.
+const TENSE_MUSIC_SPEEDUP: f32 = 0.12;
+/// Seconds the music takes to fade fully between its menu and gameplay levels.
+const MENU_FADE_TIME: f32 = 0.75;
+
 #[derive(Event)]
 pub enum PlayerInputEvent {
     MoveLeft,
     MoveRight,
     Idle,
+    /// Stand still and catch your breath.
+    Rest,
+    Jump,
 }
 
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, States)]
@@ -51,9 +118,14 @@ pub enum GameState {
     #[default]
     Startup,
     MainMenu,
+    Controls,
+    Settings,
+    Credits,
+    Stats,
     InGame,
     Pause,
     GiveUp,
+    Victory,
     Cleanup,
 }
 
@@ -62,7 +134,10 @@ fn main() {
         .insert_resource(AssetMetaCheck::Never) // Makes WASM happy
         .insert_resource(ClearColor(COLOR_BACKGROUND))
         .insert_resource(DistanceTraveled(0.))
+        .insert_resource(RunTime(0.))
+        .insert_resource(SummitDistance(500.))
         .insert_resource(PkvStore::new("LOFI", "sisyphus-simulator"))
+        .init_resource::<Playlist>()
         .init_state::<GameState>()
         .add_audio_channel::<BackgroundMusic>()
         .add_audio_channel::<SoundFX>()
@@ -74,7 +149,8 @@ fn main() {
             DefaultPlugins
                 .set(WindowPlugin {
                     primary_window: Some(Window {
-                        title: "Sisyphus Simulator".to_string(),
+                        // The version tells builds apart in bug reports.
+                        title: format!("{GAME_TITLE} v{}", env!("CARGO_PKG_VERSION")),
                         resolution: (WINDOW_WIDTH, WINDOW_HEIGHT).into(),
                         resizable: false,
                         ..default()
@@ -86,53 +162,126 @@ fn main() {
         .add_plugins(AudioPlugin) // Kira audio
         .add_plugins(TilemapPlugin) // ECS Tilemap
         .add_plugins((
-            RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(64.),
+            // Step alongside the player's FixedUpdate systems, so they can be
+            // ordered against it and never see a stale controller output.
+            RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(64.).in_fixed_schedule(),
             // RapierDebugRenderPlugin::default(),
         ))
         .add_plugins((
             AnimationPlugin,
             BoulderPlugin,
             CameraPlugin,
+            ControlsPlugin,
+            CreditsPlugin,
+            DifficultyPlugin,
             GroundPlugin,
+            HudPlugin,
+            MilestonePlugin,
+            ObstaclePlugin,
+            ParticlePlugin,
             PlayerPlugin,
+            QuitPlugin,
+            SettingsPlugin,
+            TouchPlugin,
         ))
+        .add_plugins((StatsPlugin, TutorialPlugin))
         // .add_plugins(WorldInspectorPlugin::new()) // Egui editor
         .add_systems(Startup, (setup_background_music, spawn_background))
         .add_systems(
             Update,
             (
-                volume, movement, pause,
+                volume,
+                update_volume_hint,
+                movement,
+                pause,
+                play_next_track,
+                music_intensity,
+                fade_music,
+                follow_starfield,
                 // log_transitions,
             ),
         )
-        .add_systems(OnEnter(GameState::Pause), setup_pause_menu)
+        .add_systems(OnEnter(GameState::Pause), (setup_pause_menu, pause_physics))
         .add_systems(Update, pause_menu_system.run_if(in_state(GameState::Pause)))
-        .add_systems(OnExit(GameState::Pause), cleanup_pause_menu)
-        .add_systems(OnExit(GameState::MainMenu), spawn_wall)
+        .add_systems(
+            OnExit(GameState::Pause),
+            (cleanup_pause_menu, resume_physics),
+        )
+        .add_systems(
+            OnTransition {
+                from: GameState::MainMenu,
+                to: GameState::InGame,
+            },
+            spawn_wall,
+        )
         .add_systems(OnEnter(GameState::MainMenu), setup_main_menu)
-        .add_systems(OnEnter(GameState::GiveUp), setup_give_up_menu)
+        .add_systems(
+            OnEnter(GameState::GiveUp),
+            // The menu compares against the previous best, so record afterwards.
+            (setup_give_up_menu, record_best_distance).chain(),
+        )
         .add_systems(
             Update,
-            give_up_menu_system.run_if(in_state(GameState::GiveUp)),
+            give_up_menu_system
+                .run_if(in_state(GameState::GiveUp).and_then(not(quit_confirm_open))),
         )
         .add_systems(OnExit(GameState::GiveUp), cleanup_give_up_menu)
         .add_systems(
             Update,
-            main_menu_button_system.run_if(in_state(GameState::MainMenu)),
+            (tick_run_time, reach_summit).run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(
+            OnEnter(GameState::Victory),
+            (record_best_distance, record_best_time, setup_victory_menu).chain(),
+        )
+        .add_systems(
+            Update,
+            victory_menu_system
+                .run_if(in_state(GameState::Victory).and_then(not(quit_confirm_open))),
+        )
+        .add_systems(OnExit(GameState::Victory), cleanup_victory_menu)
+        .add_systems(
+            Update,
+            main_menu_button_system
+                .run_if(in_state(GameState::MainMenu).and_then(not(quit_confirm_open))),
         )
         .add_systems(OnEnter(GameState::Cleanup), cleanup)
         .add_systems(OnExit(GameState::MainMenu), cleanup_main_menu)
         .run();
 }
 
-fn movement(keyboard_input: Res<ButtonInput<KeyCode>>, mut events: EventWriter<PlayerInputEvent>) {
-    if keyboard_input.pressed(KeyCode::ArrowLeft) {
+fn movement(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    gamepad: GamepadInput,
+    touch: TouchButtons,
+    mut events: EventWriter<PlayerInputEvent>,
+) {
+    let stick = gamepad.left_stick_x();
+    let left = bindings.left_pressed(&keyboard_input)
+        || gamepad.pressed(GamepadButtonType::DPadLeft)
+        || touch.held(TouchButton::Left)
+        || stick < 0.;
+    let right = bindings.right_pressed(&keyboard_input)
+        || gamepad.pressed(GamepadButtonType::DPadRight)
+        || touch.held(TouchButton::Right)
+        || stick > 0.;
+
+    // Holding both ways cancels out rather than favoring one.
+    if left && !right {
         events.send(PlayerInputEvent::MoveLeft);
-    } else if keyboard_input.pressed(KeyCode::ArrowRight) {
+    } else if right && !left {
         events.send(PlayerInputEvent::MoveRight);
+    } else if keyboard_input.pressed(bindings.rest) || gamepad.pressed(GamepadButtonType::West) {
+        events.send(PlayerInputEvent::Rest);
     } else {
         events.send(PlayerInputEvent::Idle);
     }
+
+    if keyboard_input.just_pressed(bindings.jump) || gamepad.just_pressed(GamepadButtonType::South)
+    {
+        events.send(PlayerInputEvent::Jump);
+    }
 }
 
 fn spawn_wall(mut commands: Commands) {
@@ -156,7 +305,38 @@ fn spawn_wall(mut commands: Commands) {
 #[derive(Component)]
 struct TitleText;
 
-fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+/// Darkens the frozen game behind the pause and give-up menus.
+#[derive(Component)]
+struct MenuDimmer;
+
+/// Spawn a `MenuDimmer` underneath everything else on the UI layer.
+fn spawn_dimmer(commands: &mut Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                ..default()
+            },
+            background_color: Color::rgba(0., 0., 0., 0.6).into(),
+            z_index: ZIndex::Global(-1),
+            ..default()
+        },
+        UI_LAYER,
+        MenuDimmer,
+    ));
+}
+
+fn setup_main_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    pkv: Res<PkvStore>,
+    bindings: Res<KeyBindings>,
+    difficulty: Res<Difficulty>,
+    boulder_size: Res<BoulderSize>,
+    volumes: Res<Volumes>,
+) {
     let title_font: Handle<Font> = asset_server.load("fonts/Kaph-Regular.ttf");
     commands
         .spawn(NodeBundle {
@@ -174,7 +354,7 @@ fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
         .with_children(|parent| {
             parent.spawn((
                 TextBundle::from_section(
-                    "Sisyphus Simulator".to_string(),
+                    GAME_TITLE.to_string(),
                     TextStyle {
                         font_size: 60.0,
                         color: Color::WHITE,
@@ -240,6 +420,87 @@ fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
                     ));
                 });
 
+            // The run options, side by side to keep the menu short.
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(10.),
+                        margin: UiRect {
+                            top: Val::Px(10.),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    let option_button = ButtonBundle {
+                        background_color: Color::PURPLE.into(),
+                        style: Style {
+                            align_items: AlignItems::Center,
+                            justify_content: JustifyContent::Center,
+                            width: Val::Px(280.),
+                            height: Val::Px(50.),
+                            ..default()
+                        },
+                        ..default()
+                    };
+
+                    parent
+                        .spawn((option_button.clone(), DifficultyButton))
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section(
+                                difficulty.label(),
+                                text_style.clone(),
+                            ));
+                        });
+
+                    parent
+                        .spawn((option_button, BoulderSizeButton))
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section(
+                                boulder_size.label(),
+                                text_style.clone(),
+                            ));
+                        });
+                });
+
+            // The other screens, side by side like the run options.
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(10.),
+                        margin: UiRect {
+                            top: Val::Px(10.),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    let screen_button = ButtonBundle {
+                        background_color: Color::PURPLE.into(),
+                        style: Style {
+                            align_items: AlignItems::Center,
+                            justify_content: JustifyContent::Center,
+                            width: Val::Px(150.),
+                            height: Val::Px(50.),
+                            ..default()
+                        },
+                        ..default()
+                    };
+
+                    for label in ["Controls", "Settings", "Stats", "Credits"] {
+                        parent.spawn(screen_button.clone()).with_children(|parent| {
+                            parent.spawn(TextBundle::from_section(
+                                label.to_string(),
+                                text_style.clone(),
+                            ));
+                        });
+                    }
+                });
+
             parent
                 .spawn((ButtonBundle {
                     background_color: Color::PURPLE.into(),
@@ -263,12 +524,25 @@ fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
                     ));
                 });
 
+            parent.spawn((
+                TextBundle::from_section(volume_hint(&bindings, volumes.muted), text_style.clone())
+                    .with_text_justify(JustifyText::Center)
+                    .with_style(Style {
+                        margin: UiRect {
+                            top: Val::Px(10.),
+                            ..default()
+                        },
+                        ..default()
+                    }),
+                UI_LAYER,
+                VolumeHint,
+            ));
+
             parent.spawn((
                 TextBundle::from_section(
-                    "-/= to lower/raise volume\n0 to mute".to_string(),
+                    format!("Best: {:.0} m", best_distance(&pkv)),
                     text_style.clone(),
                 )
-                .with_text_justify(JustifyText::Center)
                 .with_style(Style {
                     margin: UiRect {
                         top: Val::Px(10.),
@@ -297,10 +571,15 @@ fn cleanup_main_menu(
 fn main_menu_button_system(
     mut state: ResMut<NextState<GameState>>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    gamepad: GamepadInput,
     mut interaction_query: Query<(&Interaction, &Children), (Changed<Interaction>, With<Button>)>,
     mut text_query: Query<&mut Text>,
+    mut quit: EventWriter<QuitRequested>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::Space) {
+    if keyboard_input.just_pressed(bindings.confirm)
+        || gamepad.just_pressed(GamepadButtonType::South)
+    {
         state.set(GameState::InGame);
     }
 
@@ -310,8 +589,16 @@ fn main_menu_button_system(
             Interaction::Pressed => {
                 if text.sections[0].value == "Play" {
                     state.set(GameState::InGame);
+                } else if text.sections[0].value == "Controls" {
+                    state.set(GameState::Controls);
+                } else if text.sections[0].value == "Settings" {
+                    state.set(GameState::Settings);
+                } else if text.sections[0].value == "Stats" {
+                    state.set(GameState::Stats);
+                } else if text.sections[0].value == "Credits" {
+                    state.set(GameState::Credits);
                 } else if text.sections[0].value == "Quit" {
-                    std::process::exit(0);
+                    quit.send(QuitRequested);
                 }
             }
             Interaction::Hovered => {
@@ -326,29 +613,132 @@ fn main_menu_button_system(
 
 fn volume(
     keyboard_input: Res<ButtonInput<KeyCode>>,
-    music_controller: Query<&AudioSink, With<BGMusic>>,
+    bindings: Res<KeyBindings>,
+    mut control: VolumeControl,
 ) {
-    if let Ok(sink) = music_controller.get_single() {
-        if keyboard_input.just_pressed(KeyCode::Equal) {
-            sink.set_volume(sink.volume() + 0.1);
-        } else if keyboard_input.just_pressed(KeyCode::Minus) {
-            sink.set_volume(sink.volume() - 0.1);
-        } else if keyboard_input.just_pressed(KeyCode::Digit0) {
-            sink.set_volume(0.0);
-        }
+    let volume = control.volume(VolumeKind::Music);
+    if keyboard_input.just_pressed(bindings.volume_up) {
+        control.set_volume(VolumeKind::Music, volume + 0.1);
+    } else if keyboard_input.just_pressed(bindings.volume_down) {
+        control.set_volume(VolumeKind::Music, volume - 0.1);
+    } else if keyboard_input.just_pressed(bindings.mute) {
+        control.toggle_mute();
+    } else {
+        return;
+    }
+
+    control.save();
+}
+
+/// Keep the menu hint saying mute or unmute to match.
+fn update_volume_hint(
+    bindings: Res<KeyBindings>,
+    volumes: Res<Volumes>,
+    mut query: Query<&mut Text, With<VolumeHint>>,
+) {
+    if !volumes.is_changed() {
+        return;
+    }
+
+    for mut text in &mut query {
+        text.sections[0].value = volume_hint(&bindings, volumes.muted);
     }
 }
 
 #[derive(Component)]
 struct BGMusic;
 
-fn setup_background_music(mut commands: Commands, asset_server: Res<AssetServer>) {
+/// A background music track.
+struct Track {
+    path: &'static str,
+}
+
+/// The background music, played through in a shuffled order. Add a `Track` to
+/// `tracks` to put it in the rotation.
+#[derive(Resource)]
+struct Playlist {
+    tracks: Vec<Track>,
+    /// Indices into `tracks` still to play before reshuffling.
+    queue: Vec<usize>,
+}
+
+impl Default for Playlist {
+    fn default() -> Self {
+        Self {
+            tracks: vec![Track {
+                path: "music/Lost in the Dessert.ogg",
+            }],
+            queue: Vec::new(),
+        }
+    }
+}
+
+impl Playlist {
+    /// The next track, reshuffling once every track has had its turn.
+    fn next(&mut self) -> Option<&Track> {
+        if self.queue.is_empty() {
+            self.queue = (0..self.tracks.len()).collect();
+            self.queue.shuffle(&mut rand::thread_rng());
+        }
+        let index = self.queue.pop()?;
+        self.tracks.get(index)
+    }
+}
+
+fn setup_background_music(
+    background_music: Res<AudioChannel<BackgroundMusic>>,
+    sound_fx: Res<AudioChannel<SoundFX>>,
+    pkv: Res<PkvStore>,
+    mut volumes: ResMut<Volumes>,
+) {
+    volumes.music = pkv
+        .get::<f32>(VOLUME_KEY)
+        .unwrap_or(DEFAULT_VOLUME)
+        .clamp(0.0, 1.0);
+    // Saves from before the split only have the one volume; start effects there.
+    volumes.effects = pkv
+        .get::<f32>(EFFECTS_VOLUME_KEY)
+        .unwrap_or(volumes.music)
+        .clamp(0.0, 1.0);
+    volumes.muted = pkv.get::<bool>(MUTED_KEY).unwrap_or(false);
+
+    let (volume, effects_volume) = if volumes.muted {
+        (0.0, 0.0)
+    } else {
+        (volumes.music, volumes.effects)
+    };
+    background_music.set_volume(volume as f64);
+    sound_fx.set_volume(effects_volume as f64);
+}
+
+/// Start the next track in the playlist once the last one has finished.
+fn play_next_track(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut playlist: ResMut<Playlist>,
+    volumes: Res<Volumes>,
+    fade: Res<MusicFade>,
+    music: Query<(), With<BGMusic>>,
+) {
+    if !music.is_empty() {
+        return;
+    }
+    let Some(track) = playlist.next() else {
+        return;
+    };
+
+    // A fresh sink doesn't know about the volume settings yet.
+    let volume = if volumes.muted {
+        0.0
+    } else {
+        volumes.music * fade.0
+    };
     commands.spawn((
         AudioBundle {
-            source: asset_server.load("music/Lost in the Dessert.ogg"),
+            source: asset_server.load(track.path),
             settings: PlaybackSettings {
-                volume: Volume::new(0.25),
-                mode: PlaybackMode::Loop,
+                volume: Volume::new(volume),
+                mode: PlaybackMode::Despawn,
                 ..default()
             },
         },
@@ -356,12 +746,60 @@ fn setup_background_music(mut commands: Commands, asset_server: Res<AssetServer>
     ));
 }
 
+/// How loud the music plays in `state`, as a fraction of the music volume.
+/// Quieter on the menus, and quieter still while paused.
+fn music_level(state: GameState) -> f32 {
+    match state {
+        GameState::InGame => 1.0,
+        GameState::Pause => 0.4,
+        _ => 0.7,
+    }
+}
+
+/// Ease the music toward the level for the current state, rather than cutting.
+fn fade_music(time: Res<Time>, state: Res<State<GameState>>, mut control: VolumeControl) {
+    let target = music_level(*state.get());
+    let fade = control.fade();
+    if fade == target {
+        return;
+    }
+
+    let step = time.delta_seconds() / MENU_FADE_TIME;
+    control.set_fade(fade + (target - fade).clamp(-step, step));
+}
+
+/// Speed the music up as the player tires, and ease it back as they recover.
+fn music_intensity(
+    time: Res<Time>,
+    player: Query<&Fatigue, With<Player>>,
+    music: Query<&AudioSink, With<BGMusic>>,
+    mut intensity: Local<f32>,
+) {
+    let target = match player.get_single() {
+        Ok(Fatigue(fatigue)) => ((fatigue - CALM_FATIGUE) / (100. - CALM_FATIGUE)).clamp(0.0, 1.0),
+        Err(_) => 0.0,
+    };
+    let step = MUSIC_FADE_RATE * time.delta_seconds();
+    *intensity += (target - *intensity).clamp(-step, step);
+
+    // Checked every frame, since each new track starts at normal speed.
+    let speed = 1.0 + TENSE_MUSIC_SPEEDUP * *intensity;
+    if let Ok(sink) = music.get_single() {
+        if sink.speed() != speed {
+            sink.set_speed(speed);
+        }
+    }
+}
+
 fn pause(
     current_state: Res<State<GameState>>,
     mut next_state: ResMut<NextState<GameState>>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    gamepad: GamepadInput,
 ) {
-    if keyboard_input.just_pressed(KeyCode::Escape) {
+    if keyboard_input.just_pressed(bindings.pause) || gamepad.just_pressed(GamepadButtonType::Start)
+    {
         match current_state.get() {
             GameState::InGame => {
                 next_state.set(GameState::Pause);
@@ -374,7 +812,14 @@ fn pause(
     }
 }
 
-fn setup_pause_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn setup_pause_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    bindings: Res<KeyBindings>,
+    volumes: Res<Volumes>,
+) {
+    spawn_dimmer(&mut commands);
+
     let title_font: Handle<Font> = asset_server.load("fonts/Kaph-Regular.ttf");
     commands
         .spawn(NodeBundle {
@@ -482,28 +927,34 @@ fn setup_pause_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
                 });
 
             parent.spawn((
-                TextBundle::from_section(
-                    "-/= to lower/raise volume\n0 to mute".to_string(),
-                    text_style.clone(),
-                )
-                .with_text_justify(JustifyText::Center)
-                .with_style(Style {
-                    margin: UiRect {
-                        top: Val::Px(10.),
+                TextBundle::from_section(volume_hint(&bindings, volumes.muted), text_style.clone())
+                    .with_text_justify(JustifyText::Center)
+                    .with_style(Style {
+                        margin: UiRect {
+                            top: Val::Px(10.),
+                            ..default()
+                        },
                         ..default()
-                    },
-                    ..default()
-                }),
+                    }),
                 UI_LAYER,
+                VolumeHint,
             ));
         });
 }
 
 fn pause_menu_system(
     mut state: ResMut<NextState<GameState>>,
+    gamepad: GamepadInput,
     mut interaction_query: Query<(&Interaction, &Children), (Changed<Interaction>, With<Button>)>,
     mut text_query: Query<&mut Text>,
 ) {
+    // B backs out to the game like "Back", A confirms "Give Up".
+    if gamepad.just_pressed(GamepadButtonType::East) {
+        state.set(GameState::InGame);
+    } else if gamepad.just_pressed(GamepadButtonType::South) {
+        state.set(GameState::GiveUp);
+    }
+
     for (interaction, children) in &mut interaction_query {
         let mut text = text_query.get_mut(children[0]).unwrap();
         match *interaction {
@@ -528,6 +979,7 @@ fn cleanup_pause_menu(
     mut commands: Commands,
     interaction_query: Query<(Entity, &Interaction, &mut UiImage), With<Button>>,
     text_query: Query<Entity, With<Text>>,
+    dimmer: Query<Entity, With<MenuDimmer>>,
 ) {
     for entity in &text_query {
         commands.entity(entity).despawn_recursive();
@@ -535,6 +987,20 @@ fn cleanup_pause_menu(
     for entity in &mut interaction_query.iter() {
         commands.entity(entity.0).despawn_recursive();
     }
+    for entity in &dimmer {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Stop the whole simulation, player included, while the pause menu is up.
+fn pause_physics(mut rapier_config: ResMut<RapierConfiguration>) {
+    rapier_config.physics_pipeline_active = false;
+}
+
+/// Physics steps in `FixedUpdate`, which keeps ticking through the pause, so
+/// there's no time to catch up on all at once when it resumes.
+fn resume_physics(mut rapier_config: ResMut<RapierConfiguration>) {
+    rapier_config.physics_pipeline_active = true;
 }
 
 fn log_transitions(mut transitions: EventReader<StateTransitionEvent<GameState>>) {
@@ -546,8 +1012,80 @@ fn log_transitions(mut transitions: EventReader<StateTransitionEvent<GameState>>
     }
 }
 
-fn setup_give_up_menu(mut commands: Commands, asset_server: Res<AssetServer>, distance_traveled: Res<DistanceTraveled>) {
+/// The furthest distance reached across all runs, or 0 on the first run.
+fn best_distance(pkv: &PkvStore) -> f32 {
+    pkv.get::<f32>(BEST_DISTANCE_KEY).unwrap_or(0.)
+}
+
+fn record_best_distance(mut pkv: ResMut<PkvStore>, distance_traveled: Res<DistanceTraveled>) {
+    let distance = distance_traveled.0 / 64.;
+    if distance > best_distance(&pkv) {
+        if let Err(err) = pkv.set(BEST_DISTANCE_KEY, &distance) {
+            warn!("failed to save best distance: {err}");
+        }
+    }
+}
+
+fn tick_run_time(time: Res<Time>, mut run_time: ResMut<RunTime>) {
+    run_time.0 += time.delta_seconds();
+}
+
+fn best_time(pkv: &PkvStore) -> Option<f32> {
+    pkv.get::<f32>(BEST_TIME_KEY).ok()
+}
+
+fn record_best_time(mut pkv: ResMut<PkvStore>, run_time: Res<RunTime>) {
+    if !best_time(&pkv).is_some_and(|best| best <= run_time.0) {
+        if let Err(err) = pkv.set(BEST_TIME_KEY, &run_time.0) {
+            warn!("failed to save best time: {err}");
+        }
+    }
+}
+
+/// Something to say to a player who gave up at `distance`, depending on how
+/// it compares with their `best`.
+fn encouragement(distance: f32, best: f32) -> &'static [&'static str] {
+    if distance > best {
+        &[
+            "Further than ever!",
+            "That's a new record!",
+            "Look how far you got!",
+        ]
+    } else if distance >= best * 0.9 {
+        &[
+            "You almost made it!",
+            "Nearly there!",
+            "So close!",
+            "Just a bit more!",
+            "You were so close!",
+        ]
+    } else if distance >= best * 0.5 {
+        &[
+            "You'll get it next time!",
+            "Don't give up so easily!",
+            "You were halfway there!",
+            "Maybe next time!",
+        ]
+    } else {
+        &[
+            "The boulder will wait.",
+            "Every climb starts at the bottom.",
+            "One more try?",
+        ]
+    }
+}
+
+fn setup_give_up_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    distance_traveled: Res<DistanceTraveled>,
+    run_time: Res<RunTime>,
+    pkv: Res<PkvStore>,
+) {
     let distance = distance_traveled.0 / 64.;
+    let best = best_distance(&pkv);
+    spawn_dimmer(&mut commands);
+
     let title_font: Handle<Font> = asset_server.load("fonts/Kaph-Regular.ttf");
     commands
         .spawn(NodeBundle {
@@ -586,22 +1124,9 @@ fn setup_give_up_menu(mut commands: Commands, asset_server: Res<AssetServer>, di
         font,
     };
 
-    if distance > 100. {
-
-        let phrases = [
-            "You almost made it!",
-            "Nearly there!",
-            "So close!",
-            "Just a bit more!",
-            "You'll get it next time!",
-            "You were almost there!",
-            "Don't give up so easily!",
-            "You were so close!",
-            "You were almost there!",
-            "Maybe next time!"
-        ];
-
-        commands.spawn((
+    let phrases = encouragement(distance, best);
+    commands
+        .spawn((
             NodeBundle {
                 style: Style {
                     width: Val::Percent(100.),
@@ -620,17 +1145,23 @@ fn setup_give_up_menu(mut commands: Commands, asset_server: Res<AssetServer>, di
                 ..default()
             },
             UI_LAYER,
-        )).with_children(|parent| {
+        ))
+        .with_children(|parent| {
             parent.spawn((
-            TextBundle::from_section(
-                phrases.choose(&mut rand::thread_rng()).unwrap().to_string(),
-                text_style.clone(),
-            )
-            .with_text_justify(JustifyText::Center),
-            UI_LAYER,
-        ));
-    });
-    }
+                TextBundle::from_section(
+                    phrases.choose(&mut rand::thread_rng()).unwrap().to_string(),
+                    text_style.clone(),
+                )
+                .with_text_justify(JustifyText::Center),
+                UI_LAYER,
+            ));
+        });
+
+    let record = if distance > best {
+        "New best!".to_string()
+    } else {
+        format!("Best: {:.0} m", best)
+    };
 
     commands
         .spawn((
@@ -654,6 +1185,20 @@ fn setup_give_up_menu(mut commands: Commands, asset_server: Res<AssetServer>, di
             UI_LAYER,
         ))
         .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    format!(
+                        "You reached {:.0} m in {}\n{}",
+                        distance,
+                        format_time(run_time.0),
+                        record
+                    ),
+                    text_style.clone(),
+                )
+                .with_text_justify(JustifyText::Center),
+                UI_LAYER,
+            ));
+
             parent
                 .spawn((ButtonBundle {
                     background_color: Color::PURPLE.into(),
@@ -704,9 +1249,15 @@ fn setup_give_up_menu(mut commands: Commands, asset_server: Res<AssetServer>, di
 
 fn give_up_menu_system(
     mut state: ResMut<NextState<GameState>>,
+    gamepad: GamepadInput,
     mut interaction_query: Query<(&Interaction, &Children), (Changed<Interaction>, With<Button>)>,
     mut text_query: Query<&mut Text>,
+    mut quit: EventWriter<QuitRequested>,
 ) {
+    if gamepad.just_pressed(GamepadButtonType::South) {
+        state.set(GameState::Cleanup);
+    }
+
     for (interaction, children) in &mut interaction_query {
         let mut text = text_query.get_mut(children[0]).unwrap();
         match *interaction {
@@ -714,7 +1265,7 @@ fn give_up_menu_system(
                 if text.sections[0].value == "Try again" {
                     state.set(GameState::Cleanup);
                 } else if text.sections[0].value == "Quit" {
-                    std::process::exit(0);
+                    quit.send(QuitRequested);
                 }
             }
             Interaction::Hovered => {
@@ -731,6 +1282,7 @@ fn cleanup_give_up_menu(
     mut commands: Commands,
     interaction_query: Query<(Entity, &Interaction, &mut UiImage), With<Button>>,
     text_query: Query<Entity, With<Text>>,
+    dimmer: Query<Entity, With<MenuDimmer>>,
 ) {
     for entity in &text_query {
         commands.entity(entity).despawn_recursive();
@@ -738,13 +1290,230 @@ fn cleanup_give_up_menu(
     for entity in &mut interaction_query.iter() {
         commands.entity(entity.0).despawn_recursive();
     }
+    for entity in &dimmer {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn reach_summit(
+    distance_traveled: Res<DistanceTraveled>,
+    summit: Res<SummitDistance>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if distance_traveled.0 / 64. >= summit.0 {
+        next_state.set(GameState::Victory);
+    }
+}
+
+fn setup_victory_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    distance_traveled: Res<DistanceTraveled>,
+    run_time: Res<RunTime>,
+    pkv: Res<PkvStore>,
+) {
+    let distance = distance_traveled.0 / 64.;
+    let best = best_distance(&pkv);
+    let best_time = best_time(&pkv).unwrap_or(run_time.0);
+    let title_font: Handle<Font> = asset_server.load("fonts/Kaph-Regular.ttf");
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                top: Val::Px(-100.),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "You reached the summit!".to_string(),
+                    TextStyle {
+                        font_size: 60.0,
+                        color: Color::WHITE,
+                        font: title_font,
+                    },
+                )
+                .with_text_justify(JustifyText::Center),
+                UI_LAYER,
+                TitleText,
+            ));
+        });
+
+    let font = asset_server.load("fonts/PeaberryMono.ttf");
+
+    let text_style = TextStyle {
+        color: Color::WHITE,
+        font_size: 25.0,
+        font,
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    margin: UiRect {
+                        left: Val::Px(0.),
+                        right: Val::Px(0.),
+                        top: Val::Px(20.),
+                        bottom: Val::Px(0.),
+                    },
+                    ..default()
+                },
+                ..default()
+            },
+            UI_LAYER,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    format!(
+                        "The boulder rests at the top after {:.0} m in {}\nBest: {:.0} m, {}\n...but it won't stay there for long.",
+                        distance,
+                        format_time(run_time.0),
+                        best,
+                        format_time(best_time)
+                    ),
+                    text_style.clone(),
+                )
+                .with_text_justify(JustifyText::Center),
+                UI_LAYER,
+            ));
+
+            parent
+                .spawn((ButtonBundle {
+                    background_color: Color::PURPLE.into(),
+                    style: Style {
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        width: Val::Px(200.),
+                        height: Val::Px(50.),
+                        margin: UiRect {
+                            top: Val::Px(10.),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    ..default()
+                },))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Play again".to_string(),
+                        text_style.clone(),
+                    ));
+                });
+
+            parent
+                .spawn((ButtonBundle {
+                    background_color: Color::PURPLE.into(),
+                    style: Style {
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        width: Val::Px(150.),
+                        height: Val::Px(50.),
+                        margin: UiRect {
+                            top: Val::Px(10.),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    ..default()
+                },))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Quit".to_string(),
+                        text_style.clone(),
+                    ));
+                });
+        });
+}
+
+/// Buttons whose `Interaction` changed this frame.
+type ChangedButton = (Changed<Interaction>, With<Button>);
+
+fn victory_menu_system(
+    mut state: ResMut<NextState<GameState>>,
+    gamepad: GamepadInput,
+    mut interaction_query: Query<(&Interaction, &Children), ChangedButton>,
+    mut text_query: Query<&mut Text>,
+    mut quit: EventWriter<QuitRequested>,
+) {
+    if gamepad.just_pressed(GamepadButtonType::South) {
+        state.set(GameState::Cleanup);
+    }
+
+    for (interaction, children) in &mut interaction_query {
+        let mut text = text_query.get_mut(children[0]).unwrap();
+        match *interaction {
+            Interaction::Pressed => {
+                if text.sections[0].value == "Play again" {
+                    state.set(GameState::Cleanup);
+                } else if text.sections[0].value == "Quit" {
+                    quit.send(QuitRequested);
+                }
+            }
+            Interaction::Hovered => {
+                text.sections[0].style.font_size = 30.0;
+            }
+            Interaction::None => {
+                text.sections[0].style.font_size = 25.0;
+            }
+        }
+    }
+}
+
+fn cleanup_victory_menu(
+    mut commands: Commands,
+    interaction_query: Query<Entity, With<Button>>,
+    text_query: Query<Entity, With<Text>>,
+) {
+    for entity in &text_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    for entity in &interaction_query {
+        commands.entity(entity).despawn_recursive();
+    }
 }
 
-fn cleanup(mut next_state: ResMut<NextState<GameState>>, mut distance_traveled: ResMut<DistanceTraveled>) {
+/// Anything left over from a run that the next one rebuilds from scratch.
+type RunEntity = Or<(With<Player>, With<Boulder>, With<Obstacle>)>;
+
+/// Tear down the last run before going again. The player and boulder are
+/// respawned, fatigue and all, on the way back into `InGame`.
+fn cleanup(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut distance_traveled: ResMut<DistanceTraveled>,
+    mut run_time: ResMut<RunTime>,
+    mut last_milestone: ResMut<LastMilestone>,
+    run_entities: Query<Entity, RunEntity>,
+) {
+    for entity in &run_entities {
+        commands.entity(entity).despawn_recursive();
+    }
     distance_traveled.0 = 0.;
+    run_time.0 = 0.;
+    last_milestone.0 = 0;
     next_state.set(GameState::InGame);
 }
 
+/// Side length of each starfield tile, in pixels.
+const STARFIELD_TILE_SIZE: f32 = 1024.;
+
+/// The small starfield tilemap that keeps itself under the camera.
+#[derive(Component)]
+struct Starfield;
+
 fn spawn_background(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
@@ -753,7 +1522,9 @@ fn spawn_background(
     >,
 ) {
     let texture_handle: Handle<Image> = asset_server.load("textures/starfield.png");
-    let map_size = TilemapSize { x: 1024, y: 1024 };
+    // Every tile is the same, so a 3x3 patch recentered under the camera looks
+    // just like an endless field without spawning endless tiles.
+    let map_size = TilemapSize { x: 3, y: 3 };
     let tilemap_entity = commands.spawn_empty().id();
     let mut tile_storage = TileStorage::empty(map_size);
 
@@ -771,20 +1542,26 @@ fn spawn_background(
         }
     }
 
-    let tile_size = TilemapTileSize { x: 1024., y: 1024. };
+    let tile_size = TilemapTileSize {
+        x: STARFIELD_TILE_SIZE,
+        y: STARFIELD_TILE_SIZE,
+    };
     let grid_size = tile_size.into();
     let map_type = TilemapType::default();
 
-    commands.entity(tilemap_entity).insert(TilemapBundle {
-        grid_size,
-        map_type,
-        size: map_size,
-        storage: tile_storage,
-        texture: TilemapTexture::Single(texture_handle),
-        tile_size,
-        transform: get_tilemap_center_transform(&map_size, &grid_size, &map_type, 0.0),
-        ..default()
-    });
+    commands.entity(tilemap_entity).insert((
+        TilemapBundle {
+            grid_size,
+            map_type,
+            size: map_size,
+            storage: tile_storage,
+            texture: TilemapTexture::Single(texture_handle),
+            tile_size,
+            transform: get_tilemap_center_transform(&map_size, &grid_size, &map_type, 0.0),
+            ..default()
+        },
+        Starfield,
+    ));
 
     #[cfg(all(not(feature = "atlas"), feature = "render"))]
     {
@@ -795,3 +1572,22 @@ fn spawn_background(
         });
     }
 }
+
+/// Keep the starfield's middle tile under the camera. It moves a whole tile at
+/// a time, so the stars stay put relative to the world.
+fn follow_starfield(
+    camera: Query<&Transform, With<MainCamera>>,
+    mut starfield: Query<&mut Transform, (With<Starfield>, Without<MainCamera>)>,
+) {
+    let (Ok(camera), Ok(mut starfield)) = (camera.get_single(), starfield.get_single_mut()) else {
+        return;
+    };
+
+    // The map's origin is the center of its first tile, one tile down and left
+    // of the middle one.
+    let middle =
+        ((camera.translation.truncate() / STARFIELD_TILE_SIZE).floor() + 0.5) * STARFIELD_TILE_SIZE;
+    let origin = middle - Vec2::splat(STARFIELD_TILE_SIZE);
+    starfield.translation.x = origin.x;
+    starfield.translation.y = origin.y;
+}
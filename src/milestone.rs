@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+use bevy_kira_audio::{AudioChannel, AudioControl, AudioSource};
+
+use crate::{player::Player, DistanceTraveled, GameState, SoundFX};
+
+/// A popup is shown every this many meters.
+const MILESTONE_DISTANCE: u32 = 10;
+/// Seconds a popup stays on screen.
+const POPUP_LIFETIME: f32 = 1.2;
+/// How fast popups float upwards, in pixels per second.
+const POPUP_RISE_SPEED: f32 = 30.;
+/// Where popups appear, relative to the player.
+const POPUP_OFFSET: Vec3 = Vec3::new(0., 48., 8.);
+
+pub struct MilestonePlugin;
+
+impl Plugin for MilestonePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LastMilestone>()
+            .add_systems(Startup, load_chime)
+            .add_systems(
+                Update,
+                (announce_milestones, update_popups).run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(OnExit(GameState::InGame), despawn_popups);
+    }
+}
+
+/// How many milestones have been announced this run.
+#[derive(Resource, Default)]
+pub struct LastMilestone(pub u32);
+
+#[derive(Resource)]
+struct Chime(Handle<AudioSource>);
+
+/// Floating "+10 m" text that rises and fades out.
+#[derive(Component)]
+struct MilestonePopup {
+    lifetime: Timer,
+}
+
+fn load_chime(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(Chime(asset_server.load("sfx/chime.wav")));
+}
+
+fn announce_milestones(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    distance_traveled: Res<DistanceTraveled>,
+    mut last: ResMut<LastMilestone>,
+    chime: Res<Chime>,
+    sound_fx: Res<AudioChannel<SoundFX>>,
+    player: Query<&Transform, With<Player>>,
+) {
+    let reached = (distance_traveled.0 / 64.) as u32 / MILESTONE_DISTANCE;
+    if reached <= last.0 {
+        return;
+    }
+    last.0 = reached;
+
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                format!("+{MILESTONE_DISTANCE} m"),
+                TextStyle {
+                    color: Color::WHITE,
+                    font_size: 20.0,
+                    font: asset_server.load("fonts/PeaberryMono.ttf"),
+                },
+            ),
+            transform: Transform::from_translation(player.translation + POPUP_OFFSET),
+            ..default()
+        },
+        MilestonePopup {
+            lifetime: Timer::from_seconds(POPUP_LIFETIME, TimerMode::Once),
+        },
+    ));
+    sound_fx.play(chime.0.clone());
+}
+
+fn update_popups(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut MilestonePopup, &mut Transform, &mut Text)>,
+) {
+    for (entity, mut popup, mut transform, mut text) in &mut query {
+        popup.lifetime.tick(time.delta());
+        if popup.lifetime.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        transform.translation.y += POPUP_RISE_SPEED * time.delta_seconds();
+        text.sections[0]
+            .style
+            .color
+            .set_a(popup.lifetime.fraction_remaining());
+    }
+}
+
+/// Clear any popups still in the air, so the menus' cleanup doesn't have to.
+fn despawn_popups(mut commands: Commands, query: Query<Entity, With<MilestonePopup>>) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
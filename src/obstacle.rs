@@ -0,0 +1,152 @@
+use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
+use bevy_rapier2d::prelude::*;
+use rand::Rng;
+
+use crate::{
+    boulder::Boulder, player::Player, DistanceTraveled, GameState, WINDOW_HEIGHT, WINDOW_WIDTH,
+};
+
+/// A rock is dropped ahead of the player every this many meters.
+const OBSTACLE_SPACING: f32 = 30.;
+/// How far ahead of the player rocks appear, just off the right of the screen.
+const OBSTACLE_AHEAD: f32 = WINDOW_WIDTH;
+/// Rocks this far behind the player are despawned.
+const OBSTACLE_BEHIND: f32 = WINDOW_WIDTH * 2.;
+/// Range of rock radii. Rocks are partly buried, so they stick out a little
+/// more than their radius; the player's autostep has to clear the biggest.
+const OBSTACLE_RADIUS: std::ops::RangeInclusive<f32> = 10.0..=18.0;
+/// Fraction of a rock's radius sunk below the surface.
+const OBSTACLE_BURY: f32 = 0.4;
+
+pub struct ObstaclePlugin;
+
+#[derive(Component)]
+pub struct Obstacle;
+
+/// Whether the boulder is currently up against a rock, making it harder to push.
+#[derive(Resource, Default)]
+pub struct BoulderOnObstacle(pub bool);
+
+#[derive(Resource)]
+struct ObstacleMaterial(Handle<ColorMaterial>);
+
+/// How many rocks have been dropped this run.
+#[derive(Resource, Default)]
+struct ObstaclesSpawned(u32);
+
+impl Plugin for ObstaclePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BoulderOnObstacle>()
+            .init_resource::<ObstaclesSpawned>()
+            .add_systems(Startup, load_material)
+            .add_systems(
+                Update,
+                (spawn_obstacles, despawn_obstacles, check_boulder_contact)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+fn load_material(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    commands.insert_resource(ObstacleMaterial(
+        materials.add(asset_server.load("textures/stone.png")),
+    ));
+}
+
+/// Drop a rock on the slope ahead of the player each time they pass another
+/// `OBSTACLE_SPACING` meters.
+fn spawn_obstacles(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    material: Res<ObstacleMaterial>,
+    rapier_context: Res<RapierContext>,
+    distance_traveled: Res<DistanceTraveled>,
+    mut spawned: ResMut<ObstaclesSpawned>,
+    player: Query<&Transform, With<Player>>,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+
+    let due = (distance_traveled.0 / 64. / OBSTACLE_SPACING) as u32;
+    if due <= spawned.0 {
+        // Start counting again once the distance has been reset.
+        spawned.0 = due;
+        return;
+    }
+
+    // Find the surface the same way `probe_ground` does: straight down onto the ground.
+    let ray_pos = Vec2::new(
+        player.translation.x + OBSTACLE_AHEAD,
+        player.translation.y + WINDOW_HEIGHT,
+    );
+    let ray_dir = Vec2::new(0.0, -1.0);
+    let max_toi = WINDOW_HEIGHT * 2.;
+    let solid = true;
+    let filter = QueryFilter::default().only_fixed();
+
+    // The ground ahead may not have reached the physics world yet; try again next frame.
+    let Some((_, intersection)) =
+        rapier_context.cast_ray_and_get_normal(ray_pos, ray_dir, max_toi, solid, filter)
+    else {
+        return;
+    };
+    spawned.0 = due;
+
+    let radius = rand::thread_rng().gen_range(OBSTACLE_RADIUS);
+    let normal = intersection.normal;
+    let position = intersection.point + normal * radius * (1. - OBSTACLE_BURY);
+    let angle = normal.y.atan2(normal.x) - std::f32::consts::FRAC_PI_2;
+
+    commands.spawn((
+        MaterialMesh2dBundle {
+            mesh: meshes.add(RegularPolygon::new(radius, 6)).into(),
+            material: material.0.clone(),
+            transform: Transform::from_translation(position.extend(4.))
+                .with_rotation(Quat::from_rotation_z(angle)),
+            ..default()
+        },
+        RigidBody::Fixed,
+        Collider::ball(radius),
+        Friction::coefficient(0.7),
+        Obstacle,
+    ));
+}
+
+/// Keep entity counts down by clearing rocks once they're well behind the player.
+fn despawn_obstacles(
+    mut commands: Commands,
+    obstacles: Query<(Entity, &Transform), With<Obstacle>>,
+    player: Query<&Transform, With<Player>>,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+
+    for (entity, transform) in &obstacles {
+        if transform.translation.x < player.translation.x - OBSTACLE_BEHIND {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn check_boulder_contact(
+    rapier_context: Res<RapierContext>,
+    mut on_obstacle: ResMut<BoulderOnObstacle>,
+    boulder: Query<Entity, With<Boulder>>,
+    obstacles: Query<Entity, With<Obstacle>>,
+) {
+    let Ok(boulder) = boulder.get_single() else {
+        return;
+    };
+
+    on_obstacle.0 = obstacles.iter().any(|obstacle| {
+        rapier_context
+            .contact_pair(boulder, obstacle)
+            .is_some_and(|contact| contact.has_any_active_contacts())
+    });
+}
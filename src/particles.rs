@@ -0,0 +1,75 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::GameState;
+
+/// Most particles alive at once, so long runs can't pile up entities.
+const MAX_PARTICLES: usize = 200;
+/// Draw order for particles, just in front of the ground.
+const PARTICLE_Z: f32 = 7.;
+
+const COLOR_DUST: Color = Color::rgb(0.76, 0.7, 0.5);
+
+pub struct ParticlePlugin;
+
+impl Plugin for ParticlePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, update_particles.run_if(in_state(GameState::InGame)));
+    }
+}
+
+/// A short-lived square that drifts and fades out.
+#[derive(Component)]
+pub struct Particle {
+    lifetime: Timer,
+    velocity: Vec2,
+}
+
+/// Spawns particles, staying under `MAX_PARTICLES`.
+#[derive(SystemParam)]
+pub struct Particles<'w, 's> {
+    commands: Commands<'w, 's>,
+    alive: Query<'w, 's, (), With<Particle>>,
+}
+
+impl Particles<'_, '_> {
+    /// A puff of dust at `position`, `size` pixels across, drifting at
+    /// `velocity` pixels per second for `seconds`.
+    pub fn dust(&mut self, position: Vec2, velocity: Vec2, size: f32, seconds: f32) {
+        if self.alive.iter().len() >= MAX_PARTICLES {
+            return;
+        }
+
+        self.commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: COLOR_DUST,
+                    custom_size: Some(Vec2::splat(size)),
+                    ..default()
+                },
+                transform: Transform::from_translation(position.extend(PARTICLE_Z)),
+                ..default()
+            },
+            Particle {
+                lifetime: Timer::from_seconds(seconds, TimerMode::Once),
+                velocity,
+            },
+        ));
+    }
+}
+
+fn update_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+) {
+    for (entity, mut particle, mut transform, mut sprite) in &mut query {
+        particle.lifetime.tick(time.delta());
+        if particle.lifetime.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        transform.translation += (particle.velocity * time.delta_seconds()).extend(0.);
+        sprite.color.set_a(particle.lifetime.fraction_remaining());
+    }
+}
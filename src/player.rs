@@ -1,9 +1,76 @@
-use crate::animation::{AnimationIndices, AnimationTimer};
-use crate::boulder::Boulder;
-use crate::{DistanceTraveled, GameState, PlayerInputEvent};
+use crate::animation::{
+    AnimationFinished, AnimationIndices, AnimationMode, AnimationSpeed, AnimationTimer,
+    FrameDurations,
+};
+use crate::boulder::{Boulder, BoulderSize};
+use crate::camera::CameraShake;
+use crate::difficulty::Difficulty;
+use crate::obstacle::BoulderOnObstacle;
+use crate::particles::Particles;
+use crate::{DistanceTraveled, GameState, PlayerInputEvent, SoundFX};
 use bevy::math::bounding::{Aabb2d, BoundingCircle, IntersectsVolume};
-use bevy::{asset::LoadedFolder, prelude::*};
+use bevy::{
+    asset::LoadedFolder,
+    prelude::*,
+    time::{Real, Stopwatch},
+    utils::HashMap,
+};
+use bevy_kira_audio::prelude::{
+    AudioChannel, AudioControl, AudioInstance, AudioSource, AudioTween,
+};
 use bevy_rapier2d::prelude::*;
+use rand::Rng;
+use std::time::Duration;
+
+/// Where the player drops in at the start of each run.
+pub const PLAYER_SPAWN: Vec2 = Vec2::new(-50., 0.);
+
+/// Default time each animation frame is shown for, in seconds.
+const FRAME_DURATION: f32 = 0.1;
+
+/// Walking and pushing speed when fully rested, in pixels per second.
+const MOVE_SPEED: f32 = 75.0;
+/// Speed, in pixels per second, the boulder has to be rolling back downhill
+/// for touching it to knock the player over.
+const CRUSH_SPEED: f32 = 60.;
+/// Impulse the player is thrown back with when the boulder rolls onto them.
+const CRUSH_KNOCKBACK: Vec2 = Vec2::new(-4000., 2000.);
+
+/// Seconds after getting hurt during which the player can't be hurt again.
+const INVULNERABLE_TIME: f32 = 1.5;
+/// Seconds between blinks of the sprite while invulnerable.
+const FLASH_INTERVAL: f32 = 0.1;
+
+/// Game speed the moment the player starts to tumble.
+const SLOW_MOTION_SPEED: f32 = 0.3;
+/// Real-time seconds it takes to ease back up to full speed.
+const SLOW_MOTION_RECOVERY: f32 = 1.0;
+
+/// Upward speed at take-off, in pixels per second.
+const JUMP_SPEED: f32 = 250.;
+/// Downward acceleration while airborne, in pixels per second squared.
+const GRAVITY: f32 = 600.;
+/// Fastest the player can fall, in pixels per second.
+const MAX_FALL_SPEED: f32 = 300.;
+/// Downward speed kept up while grounded so the controller stays snapped to the slope.
+const GROUND_STICK_SPEED: f32 = 100.;
+/// Seconds from the start of one jump before the next, so it can't be chained.
+const JUMP_COOLDOWN: f32 = 0.6;
+/// How long after walking off an edge a jump still works.
+const COYOTE_TIME: Duration = Duration::from_millis(100);
+/// How long a jump pressed too early is held on to, in case the player lands.
+const JUMP_BUFFER: Duration = Duration::from_millis(120);
+
+/// Seconds of standing idle before the player gets bored and stretches.
+const BORED_TIME: f32 = 8.0;
+
+/// Fatigue at which the player slows to `EXHAUSTED_SPEED`.
+const EXHAUSTED_FATIGUE: f32 = 90.0;
+/// Fraction of `MOVE_SPEED` left when exhausted.
+const EXHAUSTED_SPEED: f32 = 0.3;
+/// Pushing progress, in pixels per second, below which the player counts as
+/// stuck and their fatigue holds steady.
+const STUCK_SPEED: f32 = 5.0;
 
 #[derive(Clone, Component, Copy, Debug, Default, Eq, Hash, PartialEq, States)]
 enum PlayerState {
@@ -14,6 +81,13 @@ enum PlayerState {
     Push,
     Hurt,
     Fall,
+    /// Standing still to catch a breath, recovering faster than when idle.
+    Rest,
+    Jump,
+    /// Getting back up after a tumble; input is ignored until it's done.
+    Recover,
+    /// A one-off stretch after idling for `BORED_TIME`, then back to idling.
+    Bored,
 }
 
 pub struct PlayerPlugin;
@@ -27,52 +101,189 @@ enum Direction {
     Right,
 }
 
+/// How worn out the player is, from 0 (fresh) to 100 (collapsing).
+#[derive(Component, Default, Reflect)]
+pub struct Fatigue(pub f32);
+
+/// A jump in progress, timed from take-off until the player may jump again.
 #[derive(Component)]
-struct FatigueMarker;
+struct Jumping(Timer);
 
-#[derive(Component, Default, Reflect)]
-pub struct Fatigue(f32);
+/// The player's vertical speed in pixels per second, up being positive.
+#[derive(Component, Default)]
+struct VerticalVelocity(f32);
+
+/// The ground under the player, from `probe_ground`'s ray straight down, so
+/// other systems don't need to cast their own.
+#[derive(Component)]
+pub struct GroundContact {
+    /// The surface normal, straight up on flat ground. Holds the last slope
+    /// seen while the player is in the air.
+    pub normal: Vec2,
+    /// Whether there's ground just below the player's feet.
+    pub grounded: bool,
+}
+
+impl Default for GroundContact {
+    fn default() -> Self {
+        Self {
+            normal: Vec2::Y,
+            grounded: false,
+        }
+    }
+}
+
+impl GroundContact {
+    /// How steep the ground is, from 0 when flat to 1 at `STRAIN_SLOPE` and beyond.
+    fn steepness(&self) -> f32 {
+        let angle = self.normal.y.clamp(-1., 1.).acos().to_degrees();
+        (angle / STRAIN_SLOPE).clamp(0., 1.)
+    }
+}
+
+/// How quickly the player tires and recovers, and when they collapse. A
+/// resource rather than constants so it can be tuned live in the inspector.
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct FatigueConfig {
+    /// Fatigue gained per second of pushing, before difficulty and boulder size.
+    pub push_rate: f32,
+    /// Fatigue gained per second of shoving the boulder over a rock.
+    pub obstacle_rate: f32,
+    /// Fatigue lost per second doing anything other than pushing or resting.
+    pub recovery_rate: f32,
+    /// Fatigue lost per second while resting.
+    pub rest_recovery_rate: f32,
+    /// Fatigue at which the player collapses.
+    pub collapse_threshold: f32,
+    /// Torque that tips the player over when they collapse.
+    pub collapse_torque: f32,
+}
+
+impl Default for FatigueConfig {
+    fn default() -> Self {
+        Self {
+            push_rate: 5.0,
+            obstacle_rate: 20.0,
+            recovery_rate: 25.0,
+            rest_recovery_rate: 60.0,
+            collapse_threshold: 99.0,
+            collapse_torque: 120.,
+        }
+    }
+}
+
+/// Recently hurt, so further hurts are ignored until the timer runs out.
+#[derive(Component)]
+struct Invulnerable(Timer);
+
+/// Eases the game back up to full speed after a tumble. Getting hurt again
+/// restarts the ramp from `SLOW_MOTION_SPEED` rather than slowing down further.
+#[derive(Resource)]
+struct SlowMotion(Timer);
+
+impl Default for SlowMotion {
+    fn default() -> Self {
+        let mut timer = Timer::from_seconds(SLOW_MOTION_RECOVERY, TimerMode::Once);
+        timer.tick(timer.duration());
+        SlowMotion(timer)
+    }
+}
+
+impl SlowMotion {
+    fn speed(&self) -> f32 {
+        SLOW_MOTION_SPEED.lerp(1.0, self.0.fraction())
+    }
+}
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.init_state::<PlayerState>()
             .register_type::<Fatigue>()
+            .register_type::<FatigueConfig>()
+            .init_resource::<FatigueConfig>()
+            .init_resource::<PushSound>()
+            .init_resource::<SlowMotion>()
+            .add_systems(Startup, load_sounds)
             .add_systems(OnEnter(PlayerState::Setup), load_textures)
-            .add_systems(
-                OnExit(GameState::MainMenu),
-                (
-                    start,
-                    spawn_player,
-                    setup_fatigue_marker.after(spawn_player),
-                ),
-            )
             .add_systems(
                 FixedUpdate,
                 (
                     fall,
-                    hurt,
+                    crush.after(push_boulder),
+                    hurt.after(crush),
                     movement.after(fall),
-                    rotate,
+                    jump.after(movement),
+                    probe_ground,
+                    // Let the tumble play out; `get_up` squares the player up afterwards.
+                    rotate
+                        .after(probe_ground)
+                        .run_if(not(in_state(PlayerState::Hurt))),
                     push_boulder.after(movement),
+                    update_fatigue.after(movement),
+                    fidget.after(movement),
                     update_sprite_direction,
-                    update_fatigue_marker,
                 )
+                    // Move against last step's controller output, then let
+                    // this step apply the movement.
+                    .before(PhysicsSet::SyncBackend)
                     .run_if(in_state(GameState::InGame)),
             )
             .add_systems(
                 Update,
                 (
                     check_textures.run_if(in_state(PlayerState::Setup)),
-                    fall_animation.run_if(in_state(PlayerState::Fall)),
-                    idle_animation.run_if(in_state(PlayerState::Idle)),
-                    walk_animation.run_if(in_state(PlayerState::Walk)),
-                    push_animation.run_if(in_state(PlayerState::Push)),
-                    hurt_animation.run_if(in_state(PlayerState::Hurt)),
                     update_direction,
                     // log_transitions,
-                    update_fatigue,
+                    pace_animation,
+                    play_footsteps.run_if(in_state(GameState::InGame)),
+                    strain_push_sound.run_if(in_state(PlayerState::Push)),
+                    kick_up_dust.run_if(in_state(GameState::InGame)),
+                    flash_invulnerable.run_if(in_state(GameState::InGame)),
+                    ease_slow_motion.run_if(in_state(GameState::InGame)),
                 ),
+            )
+            .add_systems(OnEnter(PlayerState::Push), start_push_sound)
+            .add_systems(OnExit(PlayerState::Push), stop_push_sound)
+            .add_systems(
+                OnExit(GameState::InGame),
+                (pause_sound_fx, suspend_slow_motion),
+            )
+            .add_systems(
+                OnEnter(GameState::InGame),
+                (resume_sound_fx, ease_slow_motion),
+            );
+
+        // A fresh player at the bottom of the hill for the first run and every retry.
+        for from in [GameState::MainMenu, GameState::Cleanup] {
+            app.add_systems(
+                OnTransition {
+                    from,
+                    to: GameState::InGame,
+                },
+                (start, spawn_player, reset_slow_motion),
             );
+        }
+
+        // Swap the sprite sheet exactly once per transition.
+        for state in [
+            PlayerState::Idle,
+            PlayerState::Walk,
+            PlayerState::Push,
+            PlayerState::Hurt,
+            PlayerState::Fall,
+            PlayerState::Rest,
+            PlayerState::Jump,
+            PlayerState::Recover,
+            PlayerState::Bored,
+        ] {
+            app.add_systems(OnEnter(state), animate_player);
+        }
+        app.add_systems(
+            OnEnter(PlayerState::Hurt),
+            (shake_camera, make_invulnerable, start_slow_motion),
+        )
+        .add_systems(OnEnter(PlayerState::Recover), get_up);
     }
 }
 
@@ -83,11 +294,174 @@ fn start(mut next_state: ResMut<NextState<PlayerState>>) {
 #[derive(Resource, Default)]
 struct PlayerSpriteFolder(Handle<LoadedFolder>);
 
-fn load_textures(mut commands: Commands, asset_server: Res<AssetServer>) {
+/// Sprite sheet, atlas layout and frame range for a single player animation.
+#[derive(Clone)]
+struct AnimationClip {
+    texture: Handle<Image>,
+    layout: Handle<TextureAtlasLayout>,
+    indices: AnimationIndices,
+    durations: Option<FrameDurations>,
+}
+
+impl AnimationClip {
+    fn new(
+        asset_server: &AssetServer,
+        texture_atlases: &mut Assets<TextureAtlasLayout>,
+        path: &'static str,
+        frames: usize,
+        mode: AnimationMode,
+    ) -> Self {
+        let layout = TextureAtlasLayout::from_grid(Vec2::new(48.0, 48.0), frames, 1, None, None);
+
+        Self {
+            texture: asset_server.load(path),
+            layout: texture_atlases.add(layout),
+            indices: AnimationIndices {
+                first: 0,
+                last: frames - 1,
+                mode,
+            },
+            durations: None,
+        }
+    }
+
+    fn with_durations(mut self, durations: &[f32]) -> Self {
+        self.durations = Some(FrameDurations(durations.to_vec()));
+        self
+    }
+
+    fn timer(&self) -> AnimationTimer {
+        let seconds = match &self.durations {
+            Some(durations) => durations[0],
+            None => FRAME_DURATION,
+        };
+        AnimationTimer::from_seconds(seconds)
+    }
+
+    fn apply(&self, commands: &mut Commands, entity: Entity, atlas: &mut TextureAtlas) {
+        atlas.layout = self.layout.clone();
+        atlas.index = self.indices.first;
+
+        let mut entity = commands.entity(entity);
+        entity
+            .insert(self.texture.clone())
+            .insert(self.indices.clone())
+            .insert(self.timer());
+        match &self.durations {
+            Some(durations) => entity.insert(durations.clone()),
+            None => entity.remove::<FrameDurations>(),
+        };
+    }
+}
+
+/// Handles for every player animation, keyed by the state it plays in. Built
+/// once so switching clips doesn't allocate a new atlas layout every frame.
+#[derive(Resource, Deref)]
+struct PlayerAnimations(HashMap<PlayerState, AnimationClip>);
+
+fn load_textures(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
+    animations: Option<Res<PlayerAnimations>>,
+) {
     // load multiple, individual sprites from a directory
     commands.insert_resource(PlayerSpriteFolder(
         asset_server.load_folder("sprites/player"),
     ));
+
+    if animations.is_some() {
+        return;
+    }
+
+    let mut clip = |path, frames, mode| {
+        AnimationClip::new(&asset_server, &mut texture_atlases, path, frames, mode)
+    };
+    commands.insert_resource(PlayerAnimations(HashMap::from([
+        // Breathe in and out rather than snapping back to the first frame.
+        (
+            PlayerState::Idle,
+            clip("sprites/player/idle-48x48.png", 10, AnimationMode::PingPong),
+        ),
+        (
+            PlayerState::Walk,
+            clip("sprites/player/walk-48x48.png", 8, AnimationMode::Loop),
+        ),
+        // Linger on the planted, straining frames of each stride.
+        (
+            PlayerState::Push,
+            clip("sprites/player/push-48x48.png", 10, AnimationMode::Loop)
+                .with_durations(&[0.12, 0.12, 0.12, 0.25, 0.25, 0.12, 0.12, 0.12, 0.25, 0.25]),
+        ),
+        // The tumble should read as a sudden collapse.
+        (
+            PlayerState::Hurt,
+            clip("sprites/player/hurt-48x48.png", 4, AnimationMode::Once)
+                .with_durations(&[0.06, 0.06, 0.06, 0.06]),
+        ),
+        (
+            PlayerState::Fall,
+            clip("sprites/player/jumping-48x48.png", 3, AnimationMode::Once),
+        ),
+        // Shares the falling sheet; the take-off frames come first.
+        (
+            PlayerState::Jump,
+            clip("sprites/player/jumping-48x48.png", 3, AnimationMode::Once),
+        ),
+        // No sheet of its own yet; quick, heavy breaths on the idle frames.
+        (
+            PlayerState::Rest,
+            clip("sprites/player/idle-48x48.png", 10, AnimationMode::PingPong)
+                .with_durations(&[0.05; 10]),
+        ),
+        // Also borrows the idle frames, played once through at a steady pace.
+        (
+            PlayerState::Recover,
+            clip("sprites/player/idle-48x48.png", 10, AnimationMode::Once)
+                .with_durations(&[0.06; 10]),
+        ),
+        // Idle frames again, drawn out into a long, lazy stretch.
+        (
+            PlayerState::Bored,
+            clip("sprites/player/idle-48x48.png", 10, AnimationMode::Once)
+                .with_durations(&[0.1, 0.1, 0.15, 0.2, 0.3, 0.4, 0.6, 0.6, 0.4, 0.3]),
+        ),
+    ])));
+}
+
+/// Walk frames, counted from the start of the clip, on which a foot lands.
+const FOOTSTEP_FRAMES: [usize; 2] = [2, 6];
+/// Range of playback rates for footsteps, so they don't all sound the same.
+const FOOTSTEP_PITCH: std::ops::RangeInclusive<f64> = 0.9..=1.1;
+
+/// Seconds between dust puffs kicked up while walking or pushing.
+const DUST_INTERVAL: f32 = 0.12;
+
+/// Slope, in degrees, at which the player sounds as strained as the ground
+/// can make them.
+const STRAIN_SLOPE: f32 = 20.;
+/// How far the push loop's pitch drops at full strain...
+const STRAIN_PITCH_DROP: f64 = 0.3;
+/// ...and its volume when there's no strain at all.
+const UNSTRAINED_VOLUME: f64 = 0.6;
+/// How far footsteps drop in pitch on the steepest ground.
+const FOOTSTEP_SLOPE_PITCH_DROP: f64 = 0.15;
+
+#[derive(Resource)]
+struct PlayerSounds {
+    footstep: Handle<AudioSource>,
+    push: Handle<AudioSource>,
+}
+
+/// The looping strain sound, while it's playing.
+#[derive(Resource, Default)]
+struct PushSound(Option<Handle<AudioInstance>>);
+
+fn load_sounds(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(PlayerSounds {
+        footstep: asset_server.load("sfx/footstep.wav"),
+        push: asset_server.load("sfx/push.wav"),
+    });
 }
 
 fn check_textures(
@@ -104,31 +478,34 @@ fn check_textures(
 
 fn spawn_player(
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
+    animations: Res<PlayerAnimations>,
+    existing: Query<(), With<Player>>,
 ) {
-    let texture: Handle<Image> = asset_server.load("sprites/player/push-48x48.png");
-    let layout = TextureAtlasLayout::from_grid(Vec2::new(48.0, 48.0), 10, 1, None, None);
-    let texture_atlas_layout = texture_atlases.add(layout);
-    let animation_indices = AnimationIndices { first: 0, last: 9 };
-    let translation = Vec3::new(-50., 0., 3.);
+    // Only ever one player, however the run was (re)started.
+    if !existing.is_empty() {
+        return;
+    }
+
+    let clip = &animations[&PlayerState::Push];
+    let translation = PLAYER_SPAWN.extend(3.);
 
-    commands.spawn((
+    let mut player = commands.spawn((
         SpriteSheetBundle {
             sprite: Sprite {
                 custom_size: Some(Vec2::new(64.0, 64.0)),
                 ..default()
             },
-            texture,
+            texture: clip.texture.clone(),
             atlas: TextureAtlas {
-                layout: texture_atlas_layout,
-                index: animation_indices.first,
+                layout: clip.layout.clone(),
+                index: clip.indices.first,
             },
             transform: Transform::from_translation(translation),
             ..default()
         },
-        animation_indices,
-        AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
+        clip.timer(),
+        clip.indices.clone(),
+        AnimationSpeed(1.0),
         Player,
         Direction::Right,
         RigidBody::Dynamic,
@@ -138,6 +515,12 @@ fn spawn_player(
             // Automatically slide down on slopes smaller than 45 degrees.
             min_slope_slide_angle: 45.0_f32.to_radians(),
             snap_to_ground: Some(CharacterLength::Absolute(0.5)),
+            // Step over the rocks the boulder has been pushed past.
+            autostep: Some(CharacterAutostep {
+                max_height: CharacterLength::Absolute(26.),
+                min_width: CharacterLength::Absolute(4.),
+                include_dynamic_bodies: false,
+            }),
             slide: true,
             ..default()
         },
@@ -153,200 +536,171 @@ fn spawn_player(
             linear_damping: 1.0,
             angular_damping: 0.7,
         },
+        Velocity::default(),
+        VerticalVelocity::default(),
     ));
-}
+    // One past the most components a bundle tuple can hold.
+    player.insert(GroundContact::default());
 
-fn idle_animation(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
-    query: Query<(Entity, &KinematicCharacterControllerOutput), With<Player>>,
-) {
-    if query.is_empty() {
-        return;
-    }
-    let (entity, output) = query.single();
-
-    let texture: Handle<Image> = asset_server.load("sprites/player/idle-48x48.png");
-    let layout = TextureAtlasLayout::from_grid(Vec2::new(48.0, 48.0), 10, 1, None, None);
-    let texture_atlas_layout = texture_atlases.add(layout);
-    let animation_indices = AnimationIndices { first: 0, last: 9 };
-
-    if output.desired_translation.x == 0.0 && output.grounded {
-        commands
-            .entity(entity)
-            .insert(texture)
-            .insert(texture_atlas_layout)
-            .insert(animation_indices);
+    if let Some(durations) = &clip.durations {
+        player.insert(durations.clone());
     }
 }
 
-fn walk_animation(
+/// Starts the clip registered for the `PlayerState` just entered. Deciding
+/// *which* state the player is in is left to the movement and physics systems.
+fn animate_player(
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
-    query: Query<(Entity, &KinematicCharacterControllerOutput), With<Player>>,
+    state: Res<State<PlayerState>>,
+    animations: Res<PlayerAnimations>,
+    mut query: Query<(Entity, &mut TextureAtlas), With<Player>>,
 ) {
-    if query.is_empty() {
-        return;
-    }
-    let (entity, output) = query.single();
-
-    let texture: Handle<Image> = asset_server.load("sprites/player/walk-48x48.png");
-    let layout = TextureAtlasLayout::from_grid(Vec2::new(48.0, 48.0), 8, 1, None, None);
-    let texture_atlas_layout = texture_atlases.add(layout);
-    let animation_indices = AnimationIndices { first: 0, last: 7 };
-
-    if output.desired_translation.x != 0.0 && output.grounded {
-        commands
-            .entity(entity)
-            .insert(texture)
-            .insert(texture_atlas_layout)
-            .insert(animation_indices);
-    }
-}
-
-fn push_animation(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
-    query: Query<(Entity, &KinematicCharacterControllerOutput), With<Player>>,
-) {
-    if query.is_empty() {
-        return;
-    }
-    let (entity, output) = query.single();
-
-    let texture: Handle<Image> = asset_server.load("sprites/player/push-48x48.png");
-    let layout = TextureAtlasLayout::from_grid(Vec2::new(48.0, 48.0), 10, 1, None, None);
-    let texture_atlas_layout = texture_atlases.add(layout);
-    let animation_indices = AnimationIndices { first: 0, last: 9 };
-
-    if output.desired_translation.x != 0.0 && output.grounded {
-        commands
-            .entity(entity)
-            .insert(texture)
-            .insert(texture_atlas_layout)
-            .insert(animation_indices);
-    }
-}
+    let (entity, mut atlas) = match query.get_single_mut() {
+        Ok(x) => x,
+        Err(_) => return,
+    };
 
-fn hurt_animation(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
-    query: Query<Entity, With<Player>>,
-) {
-    if query.is_empty() {
-        return;
+    if let Some(clip) = animations.get(state.get()) {
+        clip.apply(&mut commands, entity, &mut atlas);
     }
-    let entity = query.single();
-
-    let texture: Handle<Image> = asset_server.load("sprites/player/hurt-48x48.png");
-    let layout = TextureAtlasLayout::from_grid(Vec2::new(48.0, 48.0), 4, 1, None, None);
-    let texture_atlas_layout = texture_atlases.add(layout);
-    let animation_indices = AnimationIndices { first: 0, last: 3 };
-
-    commands
-        .entity(entity)
-        .insert(texture)
-        .insert(texture_atlas_layout)
-        .insert(animation_indices);
 }
 
-fn fall_animation(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
-    query: Query<Entity, With<Player>>,
-) {
-    if query.is_empty() {
-        return;
-    }
-    let entity = query.single();
-
-    let texture: Handle<Image> = asset_server.load("sprites/player/jumping-48x48.png");
-    let layout = TextureAtlasLayout::from_grid(Vec2::new(48.0, 48.0), 3, 1, None, None);
-    let texture_atlas_layout = texture_atlases.add(layout);
-    let animation_indices = AnimationIndices { first: 0, last: 2 };
-
-    commands
-        .entity(entity)
-        .insert(texture)
-        .insert(texture_atlas_layout)
-        .insert(animation_indices);
-}
+/// The player's controller and vertical speed, for `fall` to apply gravity.
+type FallController<'a> = (
+    &'a mut KinematicCharacterController,
+    &'a mut VerticalVelocity,
+    Option<&'a KinematicCharacterControllerOutput>,
+);
 
-fn fall(time: Res<Time>, mut query: Query<&mut KinematicCharacterController>) {
-    if query.is_empty() {
+/// Pull the player down with gravity, or hold them against the ground while
+/// they're on it.
+fn fall(time: Res<Time>, mut query: Query<FallController>) {
+    let Ok((mut player, mut velocity, output)) = query.get_single_mut() else {
         return;
-    }
+    };
+    let grounded = output.is_some_and(|output| output.grounded);
+    velocity.0 = if grounded && velocity.0 <= 0. {
+        -GROUND_STICK_SPEED
+    } else {
+        (velocity.0 - GRAVITY * time.delta_seconds()).max(-MAX_FALL_SPEED)
+    };
 
-    let mut player = query.single_mut();
-    let movement = time.delta().as_secs_f32() * -100.0;
+    let movement = velocity.0 * time.delta_seconds();
     match player.translation {
         Some(vec) => player.translation = Some(Vec2::new(vec.x, movement)),
         None => player.translation = Some(Vec2::new(0.0, movement)),
     }
 }
 
-fn rotate(
-    mut query: Query<(&mut Transform, &KinematicCharacterControllerOutput)>,
+/// Look straight down from the player for the ground, recording what's found
+/// in their `GroundContact`.
+fn probe_ground(
+    mut query: Query<(Entity, &Transform, &mut GroundContact)>,
     rapier_context: Res<RapierContext>,
 ) {
-    if query.is_empty() {
+    let Ok((entity, transform, mut contact)) = query.get_single_mut() else {
         return;
-    }
-
-    let (mut transform, output) = query.single_mut();
+    };
     let ray_pos = Vec2::new(transform.translation.x, transform.translation.y);
     let ray_dir = Vec2::new(0.0, -1.0);
-    let max_toi = 4.;
+    // From the player's middle to a little below their feet.
+    let max_toi = 32.;
     let solid = true;
-    let filter = QueryFilter::default();
+    let filter = QueryFilter::default().exclude_collider(entity);
 
-    if let Some((_, intersection)) =
-        rapier_context.cast_ray_and_get_normal(ray_pos, ray_dir, max_toi, solid, filter)
-    {
-        let hit_normal = intersection.normal;
+    match rapier_context.cast_ray_and_get_normal(ray_pos, ray_dir, max_toi, solid, filter) {
+        Some((_, intersection)) => {
+            contact.normal = intersection.normal;
+            contact.grounded = true;
+        }
+        None => contact.grounded = false,
+    }
+}
 
-        let target_angle = hit_normal.y.atan2(hit_normal.x);
-        let smooth_angle = transform
+fn rotate(
+    mut query: Query<(
+        &mut Transform,
+        &KinematicCharacterControllerOutput,
+        &GroundContact,
+    )>,
+) {
+    let Ok((mut transform, output, contact)) = query.get_single_mut() else {
+        return;
+    };
+
+    if contact.grounded && output.grounded {
+        // Stand square to the ground; on flat ground the normal points straight up.
+        let target_angle = contact.normal.y.atan2(contact.normal.x) - std::f32::consts::FRAC_PI_2;
+        transform.rotation = transform
             .rotation
             .lerp(Quat::from_rotation_z(target_angle), 0.1);
-        if output.grounded {
-            transform.rotation = smooth_angle;
-        }
     }
 }
 
+/// Fraction of full speed the player can manage at the given fatigue: all of it
+/// when rested, dropping linearly to `EXHAUSTED_SPEED` at `EXHAUSTED_FATIGUE`.
+fn fatigue_speed(fatigue: f32) -> f32 {
+    let tiredness = (fatigue / EXHAUSTED_FATIGUE).clamp(0.0, 1.0);
+    1.0 - (1.0 - EXHAUSTED_SPEED) * tiredness
+}
+
 fn movement(
     time: Res<Time>,
     mut events: EventReader<PlayerInputEvent>,
-    mut query: Query<(&Transform, &mut KinematicCharacterController)>,
+    mut query: Query<(
+        &Transform,
+        &mut KinematicCharacterController,
+        Option<&KinematicCharacterControllerOutput>,
+        &Fatigue,
+    )>,
     mut next_state: ResMut<NextState<PlayerState>>,
     mut distance_traveled: ResMut<DistanceTraveled>,
+    difficulty: Res<Difficulty>,
+    state: Res<State<PlayerState>>,
 ) {
-    if query.is_empty() {
+    let Ok((_transform, mut player, output, fatigue)) = query.get_single_mut() else {
         return;
+    };
+
+    // Still getting up; ignore input until back on their feet.
+    if *state.get() == PlayerState::Recover {
+        events.clear();
     }
 
-    let (_transform, mut player) = query.single_mut();
+    // Standing still shouldn't cut the stretch short; moving does.
+    let bored = *state.get() == PlayerState::Bored;
+    let speed = MOVE_SPEED * difficulty.speed_scale() * fatigue_speed(fatigue.0);
     let mut movement = 0.0;
+    let mut state = None;
 
     for event in events.read() {
         match event {
             PlayerInputEvent::MoveRight => {
-                movement += time.delta_seconds() * 75.0;
-                next_state.set(PlayerState::Walk);
+                movement += time.delta_seconds() * speed;
+                state = Some(PlayerState::Walk);
             }
             PlayerInputEvent::MoveLeft => {
-                movement -= time.delta_seconds() * 75.0;
-                next_state.set(PlayerState::Walk);
+                movement -= time.delta_seconds() * speed;
+                state = Some(PlayerState::Walk);
             }
+            PlayerInputEvent::Idle if bored => {}
             PlayerInputEvent::Idle => {
-                next_state.set(PlayerState::Idle);
+                state = Some(PlayerState::Idle);
+            }
+            // Only reachable while not moving, so never mid-push.
+            PlayerInputEvent::Rest => {
+                state = Some(PlayerState::Rest);
             }
+            // Handled by `jump`.
+            PlayerInputEvent::Jump => {}
+        }
+    }
+
+    // Walking and idling only make sense on the ground; `update_direction`
+    // takes care of falling.
+    if let Some(state) = state {
+        if output.is_some_and(|output| output.grounded) {
+            next_state.set(state);
         }
     }
 
@@ -356,65 +710,337 @@ fn movement(
     }
 }
 
+/// The player's controller and vertical speed, for `jump` to launch upward.
+type JumpController<'a> = (
+    Entity,
+    &'a mut KinematicCharacterController,
+    &'a mut VerticalVelocity,
+    Option<&'a KinematicCharacterControllerOutput>,
+    Option<&'a mut Jumping>,
+);
+
+/// Timing that makes jumps forgiving; see `COYOTE_TIME` and `JUMP_BUFFER`.
+#[derive(Default)]
+struct JumpWindow {
+    since_grounded: Duration,
+    since_pressed: Option<Duration>,
+}
+
+impl JumpWindow {
+    /// Advance the window by `dt` and decide whether to take off now. A press
+    /// counts for `JUMP_BUFFER` after it happens, and the ground for
+    /// `COYOTE_TIME` after leaving it; a press that fires is used up.
+    fn tick(&mut self, grounded: bool, pressed: bool, can_jump: bool, dt: Duration) -> bool {
+        self.since_grounded = if grounded {
+            Duration::ZERO
+        } else {
+            self.since_grounded + dt
+        };
+        self.since_pressed = if pressed {
+            Some(Duration::ZERO)
+        } else {
+            self.since_pressed
+                .map(|since| since + dt)
+                .filter(|since| *since <= JUMP_BUFFER)
+        };
+
+        let on_ground = self.since_grounded <= COYOTE_TIME;
+        let fire = self.since_pressed.is_some() && on_ground && can_jump;
+        if fire {
+            self.since_pressed = None;
+        }
+        fire
+    }
+}
+
+/// Take off when asked while grounded, leaving `fall` to bring the player back
+/// down. Can't be used mid-push, so hopping doesn't get the boulder up the hill
+/// any faster.
+fn jump(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut events: EventReader<PlayerInputEvent>,
+    state: Res<State<PlayerState>>,
+    mut next_state: ResMut<NextState<PlayerState>>,
+    mut query: Query<JumpController, With<Player>>,
+    mut window: Local<JumpWindow>,
+) {
+    let pressed = events
+        .read()
+        .any(|event| matches!(event, PlayerInputEvent::Jump));
+
+    let Ok((entity, mut player, mut velocity, output, jumping)) = query.get_single_mut() else {
+        return;
+    };
+
+    let grounded = output.is_some_and(|output| output.grounded);
+    let can_jump = jumping.is_none()
+        && !matches!(
+            state.get(),
+            PlayerState::Push | PlayerState::Hurt | PlayerState::Recover
+        );
+
+    if let Some(mut jumping) = jumping {
+        jumping.0.tick(time.delta());
+        if jumping.0.finished() {
+            commands.entity(entity).remove::<Jumping>();
+        }
+    }
+
+    if window.tick(grounded, pressed, can_jump, time.delta()) {
+        commands
+            .entity(entity)
+            .insert(Jumping(Timer::from_seconds(JUMP_COOLDOWN, TimerMode::Once)));
+        next_state.set(PlayerState::Jump);
+
+        velocity.0 = JUMP_SPEED;
+        let lift = velocity.0 * time.delta_seconds();
+        match player.translation {
+            Some(vec) => player.translation = Some(Vec2::new(vec.x, lift)),
+            None => player.translation = Some(Vec2::new(0.0, lift)),
+        }
+    }
+
+    // Past the top of the jump it's just a fall.
+    if *state.get() == PlayerState::Jump && velocity.0 <= 0. {
+        next_state.set(PlayerState::Fall);
+    }
+}
+
 fn push_boulder(
-    query: Query<&Transform, With<Player>>,
+    query: Query<(&Transform, &KinematicCharacterControllerOutput), With<Player>>,
     boulder_query: Query<&Transform, With<Boulder>>,
     mut next_state: ResMut<NextState<PlayerState>>,
-    mut distance_traveled: ResMut<DistanceTraveled>
+    mut distance_traveled: ResMut<DistanceTraveled>,
+    mut previous_x: Local<Option<f32>>,
+    size: Res<BoulderSize>,
 ) {
-    if query.is_empty() || boulder_query.is_empty() {
+    let (Ok((player_transform, output)), Ok(boulder_transform)) =
+        (query.get_single(), boulder_query.get_single())
+    else {
         return;
-    }
+    };
 
-    let player_transform = query.single();
-    let boulder_transform = boulder_query.single();
+    // How far the boulder has moved since the last tick.
+    let boulder_x = boulder_transform.translation.x;
+    let moved = previous_x.replace(boulder_x).map_or(0., |x| boulder_x - x);
 
-    let boulder_circle = BoundingCircle::new(boulder_transform.translation.truncate(), 64.0);
+    // Only count as pushing while actually walking into the boulder.
+    if !output.grounded || output.desired_translation.x == 0.0 {
+        return;
+    }
+
+    let boulder_circle = BoundingCircle::new(
+        boulder_transform.translation.truncate(),
+        size.radius() * boulder_transform.scale.x,
+    );
     let player_rect = Aabb2d::new(
         player_transform.translation.truncate(),
         Vec2::new(24.0, 24.0),
     );
 
     if boulder_circle.aabb_2d().intersects(&player_rect) {
-        distance_traveled.0 += 1.;
-        info!("{:2}", distance_traveled.0);
+        // Only progress up the hill counts.
+        distance_traveled.0 += moved.max(0.);
         next_state.set(PlayerState::Push);
     }
 }
 
 fn hurt(
+    config: Res<FatigueConfig>,
+    state: Res<State<PlayerState>>,
     mut next_state: ResMut<NextState<PlayerState>>,
-    mut player: Query<(&mut ExternalForce, &Fatigue), With<Player>>,
+    mut animation_finished: EventReader<AnimationFinished>,
+    mut player: Query<(Entity, &mut ExternalForce, &Fatigue, Has<Invulnerable>), With<Player>>,
 ) {
-    let (mut force, Fatigue(fatigue)) = match player.get_single_mut() {
+    let (entity, mut force, Fatigue(fatigue), invulnerable) = match player.get_single_mut() {
         Ok(x) => x,
         Err(_) => return,
     };
 
-    if *fatigue >= 99.0 {
-        // FIXME: the backward motion will mean the player is likely "Falling"
-        //   Need to hold the Hurt state for some period of time to avoid
-        //   skipping over the animation entirely.
+    // Invulnerability only keeps the player from collapsing again; the tumble
+    // they're already in keeps its torque until `get_up` takes it away.
+    if *fatigue >= config.collapse_threshold && !invulnerable {
         next_state.set(PlayerState::Hurt);
 
-        force.torque = 120.;
-    } else {
+        force.torque = config.collapse_torque;
+    } else if *state.get() != PlayerState::Hurt {
         force.torque = 0.;
     }
+
+    // Hold the Hurt state until the tumble animation has played through,
+    // otherwise the backward motion flips us straight into "Falling". Only a
+    // one-shot clip counts, so the tail of the previous looping clip doesn't.
+    let finished = animation_finished
+        .read()
+        .any(|event| event.entity == entity && event.indices.mode == AnimationMode::Once);
+    // Then hold Recover the same way while the player gets up.
+    match state.get() {
+        PlayerState::Hurt if finished => next_state.set(PlayerState::Recover),
+        PlayerState::Recover if finished => next_state.set(PlayerState::Idle),
+        PlayerState::Hurt | PlayerState::Recover => next_state.set(*state.get()),
+        _ => {}
+    }
 }
 
-fn update_direction(
+/// Stretch after standing idle for `BORED_TIME`, then go back to idling once
+/// the clip has played. Any other state starts the wait over.
+fn fidget(
+    time: Res<Time>,
+    state: Res<State<PlayerState>>,
+    mut next_state: ResMut<NextState<PlayerState>>,
+    mut animation_finished: EventReader<AnimationFinished>,
+    mut idle: Local<Stopwatch>,
+    player: Query<Entity, With<Player>>,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+    let finished = animation_finished
+        .read()
+        .any(|event| event.entity == player && event.indices.mode == AnimationMode::Once);
+
+    match state.get() {
+        PlayerState::Idle => {
+            idle.tick(time.delta());
+            // Only if `movement` is keeping the player idle this tick.
+            let still_idle = matches!(next_state.0, None | Some(PlayerState::Idle));
+            if idle.elapsed_secs() >= BORED_TIME && still_idle {
+                next_state.set(PlayerState::Bored);
+            }
+        }
+        PlayerState::Bored => {
+            idle.reset();
+            if finished && next_state.0.is_none() {
+                next_state.set(PlayerState::Idle);
+            }
+        }
+        _ => idle.reset(),
+    }
+}
+
+/// Stop the tumble and stand the player upright, so they don't get up
+/// crooked. `rotate` eases them back onto the slope from there.
+fn get_up(mut player: Query<(&mut Transform, &mut ExternalForce, &mut Velocity), With<Player>>) {
+    if let Ok((mut transform, mut force, mut velocity)) = player.get_single_mut() {
+        transform.rotation = Quat::IDENTITY;
+        force.torque = 0.;
+        velocity.angvel = 0.;
+    }
+}
+
+/// A player who can be hurt right now.
+type Vulnerable = (With<Player>, Without<Invulnerable>);
+
+/// Knock the player down when the boulder rolls back over them. Pushing it
+/// uphill keeps it moving away, so only a boulder coming back down counts.
+fn crush(
     mut commands: Commands,
-    query: Query<(Entity, &KinematicCharacterControllerOutput)>,
+    state: Res<State<PlayerState>>,
     mut next_state: ResMut<NextState<PlayerState>>,
+    rapier_context: Res<RapierContext>,
+    player: Query<(Entity, &Transform), Vulnerable>,
+    boulder: Query<(Entity, &Transform, &Velocity), With<Boulder>>,
 ) {
-    if query.is_empty() {
+    let (Ok((player, player_transform)), Ok((boulder, boulder_transform, velocity))) =
+        (player.get_single(), boulder.get_single())
+    else {
+        return;
+    };
+
+    if *state.get() == PlayerState::Hurt {
         return;
     }
 
-    let (player, output) = query.single();
+    let touching = rapier_context
+        .contact_pair(player, boulder)
+        .is_some_and(|contact| contact.has_any_active_contacts());
+    let above = boulder_transform.translation.y > player_transform.translation.y;
+    let rolling_back = velocity.linvel.x < -CRUSH_SPEED && velocity.linvel.y < 0.;
 
-    if !output.grounded {
+    if touching && above && rolling_back {
+        next_state.set(PlayerState::Hurt);
+        commands.entity(player).insert(ExternalImpulse {
+            impulse: CRUSH_KNOCKBACK,
+            torque_impulse: 0.,
+        });
+    }
+}
+
+fn shake_camera(mut shake: ResMut<CameraShake>) {
+    shake.add_trauma(0.6);
+}
+
+fn make_invulnerable(mut commands: Commands, player: Query<Entity, With<Player>>) {
+    if let Ok(player) = player.get_single() {
+        commands
+            .entity(player)
+            .insert(Invulnerable(Timer::from_seconds(
+                INVULNERABLE_TIME,
+                TimerMode::Once,
+            )));
+    }
+}
+
+fn start_slow_motion(mut slow_motion: ResMut<SlowMotion>, mut time: ResMut<Time<Virtual>>) {
+    slow_motion.0.reset();
+    time.set_relative_speed(slow_motion.speed());
+}
+
+/// Everything reading `Time` slows together: physics steps, fixed-update
+/// movement and fatigue, and animations. The ramp itself runs on real time so
+/// it always takes `SLOW_MOTION_RECOVERY` seconds.
+fn ease_slow_motion(
+    real_time: Res<Time<Real>>,
+    mut slow_motion: ResMut<SlowMotion>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    slow_motion.0.tick(real_time.delta());
+    time.set_relative_speed(slow_motion.speed());
+}
+
+/// A new run starts at full speed, even if the last one ended mid-tumble.
+fn reset_slow_motion(mut slow_motion: ResMut<SlowMotion>) {
+    *slow_motion = SlowMotion::default();
+}
+
+/// Run the menus at full speed; the ramp picks up where it left off on resuming.
+fn suspend_slow_motion(mut time: ResMut<Time<Virtual>>) {
+    time.set_relative_speed(1.0);
+}
+
+/// Blink the sprite while invulnerable, then restore its alpha and remove `Invulnerable`.
+fn flash_invulnerable(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Invulnerable, &mut Sprite)>,
+) {
+    for (entity, mut invulnerable, mut sprite) in &mut query {
+        invulnerable.0.tick(time.delta());
+
+        if invulnerable.0.finished() {
+            sprite.color.set_a(1.0);
+            commands.entity(entity).remove::<Invulnerable>();
+        } else {
+            let blink = (invulnerable.0.elapsed_secs() / (2. * FLASH_INTERVAL)).fract() < 0.5;
+            sprite.color.set_a(if blink { 0.3 } else { 1.0 });
+        }
+    }
+}
+
+fn update_direction(
+    mut commands: Commands,
+    query: Query<(Entity, &KinematicCharacterControllerOutput)>,
+    state: Res<State<PlayerState>>,
+    mut next_state: ResMut<NextState<PlayerState>>,
+) {
+    let Ok((player, output)) = query.get_single() else {
+        return;
+    };
+
+    let hurt = *state.get() == PlayerState::Hurt || next_state.0 == Some(PlayerState::Hurt);
+    let jumping = *state.get() == PlayerState::Jump || next_state.0 == Some(PlayerState::Jump);
+    if !output.grounded && !hurt && !jumping {
         next_state.set(PlayerState::Fall);
     }
 
@@ -426,11 +1052,9 @@ fn update_direction(
 }
 
 fn update_sprite_direction(mut query: Query<(&mut Sprite, &Direction)>) {
-    if query.is_empty() {
+    let Ok((mut sprite, direction)) = query.get_single_mut() else {
         return;
-    }
-
-    let (mut sprite, direction) = query.single_mut();
+    };
     match direction {
         Direction::Right => {
             sprite.flip_x = false;
@@ -441,95 +1065,161 @@ fn update_sprite_direction(mut query: Query<(&mut Sprite, &Direction)>) {
     }
 }
 
-fn setup_fatigue_marker(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
-    player: Query<&Transform, With<Player>>,
+fn log_transitions(mut transitions: EventReader<StateTransitionEvent<PlayerState>>) {
+    for transition in transitions.read() {
+        info!(
+            "transition: {:?} => {:?}",
+            transition.before, transition.after
+        );
+    }
+}
+
+// The push cycle visibly slows down as the player tires.
+fn pace_animation(
+    state: Res<State<PlayerState>>,
+    mut query: Query<(&Fatigue, &mut AnimationSpeed), With<Player>>,
 ) {
-    let texture: Handle<Image> = asset_server.load("ui/fatigue.png");
-    let layout = TextureAtlasLayout::from_grid(
-        Vec2::new(46.0, 14.0), // tile size
-        8,                     // columns
-        1,                     // rows
-        None,
-        None,
-    );
-    let texture_atlas_handle = texture_atlases.add(layout);
+    let (fatigue, mut speed) = match query.get_single_mut() {
+        Ok(x) => x,
+        Err(_) => return,
+    };
 
-    commands
-        .spawn(SpriteSheetBundle {
-            sprite: Sprite::default(),
-            atlas: TextureAtlas {
-                layout: texture_atlas_handle,
-                index: 0,
-            },
-            texture,
-            transform: Transform {
-                translation: player.single().translation + Vec3::new(0.0, 32.0, 100.0),
-                ..default()
-            },
-            ..default()
-        })
-        .insert(FatigueMarker);
+    speed.0 = match state.get() {
+        PlayerState::Push => 1.0 - fatigue.0 / 200.0,
+        _ => 1.0,
+    };
 }
 
-fn update_fatigue_marker(
-    mut commands: Commands,
-    player: Query<(&Transform, &Fatigue), With<Player>>,
-    mut entity: Query<(Entity, &mut TextureAtlas), With<FatigueMarker>>,
+/// Current frame and clip range of an animated sprite.
+type AnimationFrame<'a> = (&'a TextureAtlas, &'a AnimationIndices);
+
+fn play_footsteps(
+    state: Res<State<PlayerState>>,
+    sounds: Res<PlayerSounds>,
+    sound_fx: Res<AudioChannel<SoundFX>>,
+    query: Query<AnimationFrame, With<Player>>,
+    ground: Query<&GroundContact, With<Player>>,
+    mut last_index: Local<Option<usize>>,
 ) {
-    if entity.is_empty() || player.is_empty() {
+    let (Ok((atlas, indices)), Ok(ground)) = (query.get_single(), ground.get_single()) else {
+        return;
+    };
+
+    // Step once as the foot lands, not on every tick the frame is shown.
+    let changed = *last_index != Some(atlas.index);
+    *last_index = Some(atlas.index);
+    if !changed || *state.get() != PlayerState::Walk {
         return;
     }
 
-    let (transform, fatigue) = player.single();
+    let frame = atlas.index.checked_sub(indices.first);
+    if frame.is_some_and(|frame| FOOTSTEP_FRAMES.contains(&frame)) {
+        // Heavier steps going uphill.
+        let pitch = rand::thread_rng().gen_range(FOOTSTEP_PITCH)
+            * (1. - FOOTSTEP_SLOPE_PITCH_DROP * ground.steepness() as f64);
+        sound_fx
+            .play(sounds.footstep.clone())
+            .with_playback_rate(pitch);
+    }
+}
 
-    let (entity, mut atlas) = entity.single_mut();
+/// Puff a little dust from the player's heels while they walk or push.
+fn kick_up_dust(
+    time: Res<Time>,
+    state: Res<State<PlayerState>>,
+    mut particles: Particles,
+    query: Query<(&Transform, &Direction, &KinematicCharacterControllerOutput), With<Player>>,
+    mut since_last: Local<f32>,
+) {
+    let Ok((transform, direction, output)) = query.get_single() else {
+        return;
+    };
 
-    match fatigue.0.ceil() as usize {
-        0..=15 => {
-            atlas.index = 0;
-        }
-        16..=30 => {
-            atlas.index = 1;
-        }
-        31..=45 => {
-            atlas.index = 2;
-        }
-        46..=60 => {
-            atlas.index = 4;
-        }
-        61..=75 => {
-            atlas.index = 5;
-        }
-        76..=90 => {
-            atlas.index = 6;
-        }
-        _ => {
-            atlas.index = 7;
-        }
+    *since_last += time.delta_seconds();
+    let moving = matches!(state.get(), PlayerState::Walk | PlayerState::Push);
+    if !moving || !output.grounded || *since_last < DUST_INTERVAL {
+        return;
     }
+    *since_last = 0.;
 
-    commands.entity(entity).insert(Transform {
-        translation: transform.translation + Vec3::new(0.0, 32.0, 100.0),
-        ..default()
-    });
+    // Out from under the feet, away from the way they're heading.
+    let behind = match direction {
+        Direction::Left => 1.,
+        Direction::Right => -1.,
+    };
+    let feet = transform.translation + transform.rotation * Vec3::new(0., -24., 0.);
+    let mut rng = rand::thread_rng();
+    let velocity = Vec2::new(
+        behind * rng.gen_range(10.0..30.0),
+        rng.gen_range(10.0..25.0),
+    );
+    particles.dust(feet.truncate(), velocity, rng.gen_range(2.0..4.0), 0.5);
 }
 
-fn log_transitions(mut transitions: EventReader<StateTransitionEvent<PlayerState>>) {
-    for transition in transitions.read() {
-        info!(
-            "transition: {:?} => {:?}",
-            transition.before, transition.after
-        );
+fn start_push_sound(
+    mut push_sound: ResMut<PushSound>,
+    sounds: Res<PlayerSounds>,
+    sound_fx: Res<AudioChannel<SoundFX>>,
+) {
+    push_sound.0 = Some(sound_fx.play(sounds.push.clone()).looped().handle());
+}
+
+/// Make the push loop lower and louder the steeper the slope and the more
+/// tired the player is.
+fn strain_push_sound(
+    push_sound: Res<PushSound>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+    player: Query<(&GroundContact, &Fatigue), With<Player>>,
+) {
+    let Ok((ground, fatigue)) = player.get_single() else {
+        return;
+    };
+    let Some(instance) = push_sound
+        .0
+        .as_ref()
+        .and_then(|handle| audio_instances.get_mut(handle))
+    else {
+        return;
+    };
+
+    let strain = ((ground.steepness() + fatigue.0 / 100.) / 2.) as f64;
+    instance.set_playback_rate(1. - STRAIN_PITCH_DROP * strain, AudioTween::default());
+    instance.set_volume(
+        UNSTRAINED_VOLUME + (1. - UNSTRAINED_VOLUME) * strain,
+        AudioTween::default(),
+    );
+}
+
+fn stop_push_sound(
+    mut push_sound: ResMut<PushSound>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    if let Some(handle) = push_sound.0.take() {
+        if let Some(instance) = audio_instances.get_mut(&handle) {
+            instance.stop(AudioTween::default());
+        }
     }
 }
 
+/// Keep the push loop from droning on over the menus.
+fn pause_sound_fx(sound_fx: Res<AudioChannel<SoundFX>>) {
+    sound_fx.pause();
+}
+
+fn resume_sound_fx(sound_fx: Res<AudioChannel<SoundFX>>) {
+    sound_fx.resume();
+}
+
+/// Tire the player by how far the controller really moved them up the hill,
+/// so pushing against a stuck boulder doesn't count. Runs on the fixed timestep.
 fn update_fatigue(
-    time: Res<Time>,
-    mut query: Query<&mut Fatigue, With<Player>>,
+    time: Res<Time<Fixed>>,
+    config: Res<FatigueConfig>,
+    mut query: Query<(&mut Fatigue, Option<&KinematicCharacterControllerOutput>), With<Player>>,
     next_state: Res<NextState<PlayerState>>,
+    on_obstacle: Res<BoulderOnObstacle>,
+    difficulty: Res<Difficulty>,
+    size: Res<BoulderSize>,
 ) {
     let state = match next_state.0 {
         Some(state) => state,
@@ -537,16 +1227,70 @@ fn update_fatigue(
         None => return,
     };
 
-    let mut fatigue = match query.get_single_mut() {
+    let (mut fatigue, output) = match query.get_single_mut() {
         Err(_) => return,
-        Ok(fatigue) => fatigue,
+        Ok(x) => x,
     };
 
-    let updated = match state {
-        PlayerState::Push => fatigue.0 + 5.0 * time.delta_seconds(),
-        _ => fatigue.0 - 25.0 * time.delta_seconds(),
-    }
-    .clamp(0.0, 100.0);
+    let speed = output.map_or(0., |output| output.effective_translation.x) / time.delta_seconds();
+    let progressing = speed > STUCK_SPEED;
+
+    let push_scale = difficulty.fatigue_scale() * size.fatigue_scale();
+    let rate = match state {
+        // Shoving the boulder over a rock takes it out of you, moving or not.
+        PlayerState::Push if on_obstacle.0 => config.obstacle_rate * push_scale,
+        PlayerState::Push if progressing => config.push_rate * push_scale,
+        // Stuck or being pushed back: no progress, but no rest either.
+        PlayerState::Push => 0.0,
+        PlayerState::Rest => -config.rest_recovery_rate,
+        _ => -config.recovery_rate,
+    };
+    let updated = (fatigue.0 + rate * time.delta_seconds()).clamp(0.0, 100.0);
 
     fatigue.0 = updated;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: Duration = Duration::from_millis(1);
+    /// Long enough in the air that coyote time is well gone.
+    const AIRBORNE: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn coyote_time_allows_a_late_jump() {
+        let mut window = JumpWindow::default();
+        window.tick(true, false, true, MS);
+
+        assert!(window.tick(false, true, true, COYOTE_TIME));
+    }
+
+    #[test]
+    fn coyote_time_runs_out() {
+        let mut window = JumpWindow::default();
+        window.tick(true, false, true, MS);
+
+        assert!(!window.tick(false, true, true, COYOTE_TIME + MS));
+    }
+
+    #[test]
+    fn buffered_press_fires_on_landing() {
+        let mut window = JumpWindow::default();
+        window.tick(false, false, true, AIRBORNE);
+        assert!(!window.tick(false, true, true, MS));
+
+        assert!(window.tick(true, false, true, JUMP_BUFFER));
+        // The press is spent, so staying on the ground doesn't jump again.
+        assert!(!window.tick(true, false, true, MS));
+    }
+
+    #[test]
+    fn buffered_press_expires() {
+        let mut window = JumpWindow::default();
+        window.tick(false, false, true, AIRBORNE);
+        assert!(!window.tick(false, true, true, MS));
+
+        assert!(!window.tick(true, false, true, JUMP_BUFFER + MS));
+    }
+}
@@ -0,0 +1,162 @@
+use bevy::{app::AppExit, prelude::*, ui::FocusPolicy};
+
+use crate::{
+    camera::UI_LAYER,
+    input::{GamepadInput, KeyBindings},
+};
+
+pub struct QuitPlugin;
+
+impl Plugin for QuitPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<QuitRequested>().add_systems(
+            Update,
+            (
+                open_quit_confirm,
+                (quit_confirm_system, dismiss_quit_confirm).run_if(quit_confirm_open),
+            ),
+        );
+    }
+}
+
+/// Sent by a menu's Quit button to ask the player whether they mean it.
+#[derive(Event)]
+pub struct QuitRequested;
+
+/// The "Really quit?" overlay, covering whichever menu it was opened from.
+#[derive(Component)]
+pub struct QuitConfirm;
+
+#[derive(Component, Clone, Copy, PartialEq)]
+enum QuitChoice {
+    Yes,
+    No,
+}
+
+/// Whether the quit overlay is up, so the menu underneath can stand down.
+pub fn quit_confirm_open(overlay: Query<(), With<QuitConfirm>>) -> bool {
+    !overlay.is_empty()
+}
+
+fn open_quit_confirm(
+    mut commands: Commands,
+    mut requests: EventReader<QuitRequested>,
+    asset_server: Res<AssetServer>,
+    overlay: Query<(), With<QuitConfirm>>,
+) {
+    if requests.read().last().is_none() || !overlay.is_empty() {
+        return;
+    }
+
+    let text_style = TextStyle {
+        color: Color::WHITE,
+        font_size: 25.0,
+        font: asset_server.load("fonts/PeaberryMono.ttf"),
+    };
+
+    let button = ButtonBundle {
+        background_color: Color::PURPLE.into(),
+        style: Style {
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            width: Val::Px(100.),
+            height: Val::Px(50.),
+            ..default()
+        },
+        ..default()
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(20.),
+                    ..default()
+                },
+                background_color: Color::rgba(0., 0., 0., 0.8).into(),
+                // Keep clicks from reaching the menu underneath.
+                focus_policy: FocusPolicy::Block,
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            UI_LAYER,
+            QuitConfirm,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Really quit?".to_string(),
+                text_style.clone(),
+            ));
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(20.),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for (choice, label) in [(QuitChoice::Yes, "Yes"), (QuitChoice::No, "No")] {
+                        parent
+                            .spawn((button.clone(), choice))
+                            .with_children(|parent| {
+                                parent.spawn(TextBundle::from_section(
+                                    label.to_string(),
+                                    text_style.clone(),
+                                ));
+                            });
+                    }
+                });
+        });
+}
+
+fn quit_confirm_system(
+    mut commands: Commands,
+    mut exit: EventWriter<AppExit>,
+    overlay: Query<Entity, With<QuitConfirm>>,
+    interaction_query: Query<(&Interaction, &QuitChoice, &Children), Changed<Interaction>>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (interaction, choice, children) in &interaction_query {
+        let mut text = text_query.get_mut(children[0]).unwrap();
+        match (*interaction, *choice) {
+            // Let the app shut down normally rather than cutting it off mid-write.
+            (Interaction::Pressed, QuitChoice::Yes) => {
+                exit.send(AppExit);
+            }
+            (Interaction::Pressed, QuitChoice::No) => {
+                for entity in &overlay {
+                    commands.entity(entity).despawn_recursive();
+                }
+            }
+            (Interaction::Hovered, _) => text.sections[0].style.font_size = 30.0,
+            (Interaction::None, _) => text.sections[0].style.font_size = 25.0,
+        }
+    }
+}
+
+/// Back out of quitting with the pause key or the gamepad's back button.
+fn dismiss_quit_confirm(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    gamepad: GamepadInput,
+    overlay: Query<Entity, With<QuitConfirm>>,
+) {
+    if !keyboard_input.just_pressed(bindings.pause)
+        && !gamepad.just_pressed(GamepadButtonType::East)
+    {
+        return;
+    }
+
+    for entity in &overlay {
+        commands.entity(entity).despawn_recursive();
+    }
+}
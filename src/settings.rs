@@ -0,0 +1,553 @@
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    ui::RelativeCursorPosition,
+    window::{PrimaryWindow, WindowMode},
+};
+use bevy_kira_audio::prelude::{AudioChannel, AudioControl};
+use bevy_pkv::PkvStore;
+
+use crate::{
+    camera::UI_LAYER,
+    input::{GamepadInput, KeyBindings},
+    BGMusic, BackgroundMusic, GameState, SoundFX, DEFAULT_VOLUME, EFFECTS_VOLUME_KEY, MUTED_KEY,
+    VOLUME_KEY, WINDOW_HEIGHT, WINDOW_WIDTH,
+};
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Volumes>()
+            .init_resource::<MusicFade>()
+            .add_systems(Startup, (load_window_mode, load_window_scale))
+            .add_systems(OnEnter(GameState::Settings), setup_settings_menu)
+            .add_systems(Update, toggle_fullscreen)
+            .add_systems(
+                Update,
+                (
+                    settings_menu_system,
+                    drag_volume_slider,
+                    update_volume_slider.after(drag_volume_slider),
+                    update_fullscreen_text,
+                )
+                    .run_if(in_state(GameState::Settings)),
+            )
+            .add_systems(OnExit(GameState::Settings), cleanup_settings_menu);
+    }
+}
+
+/// PkvStore key for whether the game runs borderless fullscreen.
+const FULLSCREEN_KEY: &str = "fullscreen";
+/// PkvStore key for the `WindowScale`.
+const WINDOW_SCALE_KEY: &str = "window_scale";
+/// Largest multiple of the base resolution offered for the window size.
+const MAX_WINDOW_SCALE: u32 = 3;
+
+/// The windowed size, as a whole multiple of `WINDOW_WIDTH` by `WINDOW_HEIGHT`.
+#[derive(Resource)]
+struct WindowScale(u32);
+
+/// Which of the two volume levels something refers to.
+#[derive(Clone, Copy, PartialEq)]
+pub enum VolumeKind {
+    Music,
+    Effects,
+}
+
+impl VolumeKind {
+    fn label(self) -> &'static str {
+        match self {
+            VolumeKind::Music => "Music",
+            VolumeKind::Effects => "Effects",
+        }
+    }
+}
+
+/// The player's volume levels, from 0 to 1. They're kept while muted so
+/// unmuting goes back to them.
+#[derive(Resource)]
+pub struct Volumes {
+    pub music: f32,
+    pub effects: f32,
+    pub muted: bool,
+}
+
+impl Default for Volumes {
+    fn default() -> Self {
+        Self {
+            music: DEFAULT_VOLUME,
+            effects: DEFAULT_VOLUME,
+            muted: false,
+        }
+    }
+}
+
+/// How loud the music currently plays, as a fraction of the music volume, so
+/// it can dip on the menus without touching the player's setting.
+#[derive(Resource)]
+pub struct MusicFade(pub f32);
+
+impl Default for MusicFade {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Reads and changes the game volume. `Volumes` is the source of truth; the
+/// `AudioSink` on `BGMusic` and the kira channels are kept in step with it.
+#[derive(SystemParam)]
+pub struct VolumeControl<'w, 's> {
+    music: Query<'w, 's, &'static AudioSink, With<BGMusic>>,
+    background_music: Res<'w, AudioChannel<BackgroundMusic>>,
+    sound_fx: Res<'w, AudioChannel<SoundFX>>,
+    pkv: ResMut<'w, PkvStore>,
+    volumes: ResMut<'w, Volumes>,
+    fade: ResMut<'w, MusicFade>,
+}
+
+impl VolumeControl<'_, '_> {
+    pub fn volume(&self, kind: VolumeKind) -> f32 {
+        match kind {
+            VolumeKind::Music => self.volumes.music,
+            VolumeKind::Effects => self.volumes.effects,
+        }
+    }
+
+    /// Change a volume. While muted this only changes what unmuting goes
+    /// back to.
+    pub fn set_volume(&mut self, kind: VolumeKind, volume: f32) {
+        let volume = volume.clamp(0.0, 1.0);
+        match kind {
+            VolumeKind::Music => self.volumes.music = volume,
+            VolumeKind::Effects => self.volumes.effects = volume,
+        }
+        self.apply();
+    }
+
+    pub fn fade(&self) -> f32 {
+        self.fade.0
+    }
+
+    /// Play the music at `fade` times its volume.
+    pub fn set_fade(&mut self, fade: f32) {
+        self.fade.0 = fade.clamp(0.0, 1.0);
+        self.apply();
+    }
+
+    /// Silence everything, or bring it back to where it was before.
+    pub fn toggle_mute(&mut self) {
+        self.volumes.muted = !self.volumes.muted;
+        self.apply();
+    }
+
+    fn apply(&self) {
+        let (music, effects) = if self.volumes.muted {
+            (0.0, 0.0)
+        } else {
+            (self.volumes.music * self.fade.0, self.volumes.effects)
+        };
+        if let Ok(sink) = self.music.get_single() {
+            sink.set_volume(music);
+        }
+        self.background_music.set_volume(music as f64);
+        self.sound_fx.set_volume(effects as f64);
+    }
+
+    /// Persist the volumes and mute so they're restored on the next launch.
+    pub fn save(&mut self) {
+        let saved = [
+            (VOLUME_KEY, self.volumes.music),
+            (EFFECTS_VOLUME_KEY, self.volumes.effects),
+        ];
+        for (key, volume) in saved {
+            if let Err(err) = self.pkv.set(key, &volume) {
+                warn!("failed to save volume: {err}");
+            }
+        }
+        let muted = self.volumes.muted;
+        if let Err(err) = self.pkv.set(MUTED_KEY, &muted) {
+            warn!("failed to save mute: {err}");
+        }
+    }
+}
+
+/// The clickable track of a volume slider.
+#[derive(Component)]
+struct VolumeSlider(VolumeKind);
+
+/// The filled part of a volume slider.
+#[derive(Component)]
+struct VolumeFill(VolumeKind);
+
+#[derive(Component)]
+struct VolumeText(VolumeKind);
+
+#[derive(Component)]
+struct FullscreenText;
+
+fn window_scale_label(scale: u32) -> String {
+    format!("Window Size: {scale}x")
+}
+
+fn fullscreen_label(mode: WindowMode) -> String {
+    let state = match mode {
+        WindowMode::Windowed => "Off",
+        _ => "On",
+    };
+    format!("Fullscreen: {state}")
+}
+
+/// Flip between windowed and borderless fullscreen, remembering the choice.
+fn switch_window_mode(window: &mut Window, pkv: &mut PkvStore) {
+    window.mode = match window.mode {
+        WindowMode::Windowed => WindowMode::BorderlessFullscreen,
+        _ => WindowMode::Windowed,
+    };
+
+    let fullscreen = window.mode != WindowMode::Windowed;
+    if let Err(err) = pkv.set(FULLSCREEN_KEY, &fullscreen) {
+        warn!("failed to save window mode: {err}");
+    }
+}
+
+fn load_window_mode(pkv: Res<PkvStore>, mut window: Query<&mut Window, With<PrimaryWindow>>) {
+    let Ok(mut window) = window.get_single_mut() else {
+        return;
+    };
+
+    if pkv.get::<bool>(FULLSCREEN_KEY).unwrap_or(false) {
+        window.mode = WindowMode::BorderlessFullscreen;
+    }
+}
+
+/// Size the window to `scale` times the base resolution. `letterbox` takes care
+/// of scaling the game and the UI to fit.
+fn apply_window_scale(window: &mut Window, scale: u32) {
+    window
+        .resolution
+        .set(WINDOW_WIDTH * scale as f32, WINDOW_HEIGHT * scale as f32);
+}
+
+fn load_window_scale(
+    mut commands: Commands,
+    pkv: Res<PkvStore>,
+    mut window: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let scale = pkv
+        .get::<u32>(WINDOW_SCALE_KEY)
+        .unwrap_or(1)
+        .clamp(1, MAX_WINDOW_SCALE);
+    if let Ok(mut window) = window.get_single_mut() {
+        apply_window_scale(&mut window, scale);
+    }
+    commands.insert_resource(WindowScale(scale));
+}
+
+fn toggle_fullscreen(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut window: Query<&mut Window, With<PrimaryWindow>>,
+    mut pkv: ResMut<PkvStore>,
+) {
+    if !keyboard_input.just_pressed(bindings.fullscreen) {
+        return;
+    }
+
+    if let Ok(mut window) = window.get_single_mut() {
+        switch_window_mode(&mut window, &mut pkv);
+    }
+}
+
+fn setup_settings_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    scale: Res<WindowScale>,
+) {
+    let mode = window
+        .get_single()
+        .map_or(WindowMode::Windowed, |window| window.mode);
+
+    let title_font: Handle<Font> = asset_server.load("fonts/Kaph-Regular.ttf");
+    let font = asset_server.load("fonts/PeaberryMono.ttf");
+
+    let text_style = TextStyle {
+        color: Color::WHITE,
+        font_size: 25.0,
+        font,
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            UI_LAYER,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "Settings".to_string(),
+                    TextStyle {
+                        font_size: 60.0,
+                        color: Color::WHITE,
+                        font: title_font,
+                    },
+                ),
+                UI_LAYER,
+            ));
+
+            for kind in [VolumeKind::Music, VolumeKind::Effects] {
+                parent.spawn((
+                    TextBundle::from_section(kind.label().to_string(), text_style.clone())
+                        .with_style(Style {
+                            margin: UiRect {
+                                top: Val::Px(10.),
+                                ..default()
+                            },
+                            ..default()
+                        }),
+                    UI_LAYER,
+                    VolumeText(kind),
+                ));
+
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            background_color: Color::DARK_GRAY.into(),
+                            border_color: Color::WHITE.into(),
+                            style: Style {
+                                width: Val::Px(300.),
+                                height: Val::Px(20.),
+                                border: UiRect::all(Val::Px(2.)),
+                                margin: UiRect {
+                                    top: Val::Px(6.),
+                                    ..default()
+                                },
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        RelativeCursorPosition::default(),
+                        VolumeSlider(kind),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            NodeBundle {
+                                background_color: Color::PURPLE.into(),
+                                style: Style {
+                                    width: Val::Percent(0.),
+                                    height: Val::Percent(100.),
+                                    ..default()
+                                },
+                                ..default()
+                            },
+                            VolumeFill(kind),
+                        ));
+                    });
+            }
+
+            parent
+                .spawn(ButtonBundle {
+                    background_color: Color::PURPLE.into(),
+                    style: Style {
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        width: Val::Px(300.),
+                        height: Val::Px(50.),
+                        margin: UiRect {
+                            top: Val::Px(20.),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section(fullscreen_label(mode), text_style.clone()),
+                        FullscreenText,
+                    ));
+                });
+
+            parent
+                .spawn(ButtonBundle {
+                    background_color: Color::PURPLE.into(),
+                    style: Style {
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        width: Val::Px(300.),
+                        height: Val::Px(50.),
+                        margin: UiRect {
+                            top: Val::Px(20.),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        window_scale_label(scale.0),
+                        text_style.clone(),
+                    ));
+                });
+
+            parent
+                .spawn(ButtonBundle {
+                    background_color: Color::PURPLE.into(),
+                    style: Style {
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        width: Val::Px(150.),
+                        height: Val::Px(50.),
+                        margin: UiRect {
+                            top: Val::Px(20.),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Back".to_string(),
+                        text_style.clone(),
+                    ));
+                });
+        });
+}
+
+/// Buttons whose `Interaction` changed this frame, other than the slider.
+type ChangedButton = (Changed<Interaction>, With<Button>, Without<VolumeSlider>);
+
+fn settings_menu_system(
+    mut state: ResMut<NextState<GameState>>,
+    gamepad: GamepadInput,
+    interaction_query: Query<(&Interaction, &Children), ChangedButton>,
+    mut text_query: Query<&mut Text>,
+    mut window: Query<&mut Window, With<PrimaryWindow>>,
+    mut pkv: ResMut<PkvStore>,
+    mut scale: ResMut<WindowScale>,
+) {
+    if gamepad.just_pressed(GamepadButtonType::East) {
+        state.set(GameState::MainMenu);
+    }
+
+    for (interaction, children) in &interaction_query {
+        let mut text = text_query.get_mut(children[0]).unwrap();
+        match *interaction {
+            Interaction::Pressed => {
+                if text.sections[0].value == "Back" {
+                    state.set(GameState::MainMenu);
+                } else if text.sections[0].value.starts_with("Fullscreen") {
+                    if let Ok(mut window) = window.get_single_mut() {
+                        switch_window_mode(&mut window, &mut pkv);
+                    }
+                } else if text.sections[0].value.starts_with("Window Size") {
+                    scale.0 = scale.0 % MAX_WINDOW_SCALE + 1;
+                    if let Ok(mut window) = window.get_single_mut() {
+                        apply_window_scale(&mut window, scale.0);
+                    }
+                    if let Err(err) = pkv.set(WINDOW_SCALE_KEY, &scale.0) {
+                        warn!("failed to save window scale: {err}");
+                    }
+                    text.sections[0].value = window_scale_label(scale.0);
+                }
+            }
+            Interaction::Hovered => {
+                text.sections[0].style.font_size = 30.0;
+            }
+            Interaction::None => {
+                text.sections[0].style.font_size = 25.0;
+            }
+        }
+    }
+}
+
+/// Set a volume from where its slider is clicked or dragged, saving once the
+/// mouse is let go.
+fn drag_volume_slider(
+    mut control: VolumeControl,
+    sliders: Query<(&Interaction, &RelativeCursorPosition, &VolumeSlider)>,
+    mut dragging: Local<bool>,
+) {
+    let mut pressed = false;
+    for (interaction, cursor, slider) in &sliders {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        pressed = true;
+        if let Some(position) = cursor.normalized {
+            control.set_volume(slider.0, position.x);
+        }
+    }
+
+    if pressed {
+        *dragging = true;
+    } else if *dragging {
+        *dragging = false;
+        control.save();
+    }
+}
+
+/// Keep the sliders in sync with the volumes, including the -/=/0 keys.
+fn update_volume_slider(
+    volumes: Res<Volumes>,
+    mut fill: Query<(&mut Style, &VolumeFill)>,
+    mut text: Query<(&mut Text, &VolumeText)>,
+) {
+    let volume = |kind| match kind {
+        VolumeKind::Music => volumes.music,
+        VolumeKind::Effects => volumes.effects,
+    };
+
+    for (mut style, fill) in &mut fill {
+        style.width = Val::Percent(volume(fill.0) * 100.);
+    }
+    for (mut text, label) in &mut text {
+        let muted = if volumes.muted { " (muted)" } else { "" };
+        text.sections[0].value = format!(
+            "{}: {:.0}%{}",
+            label.0.label(),
+            volume(label.0) * 100.,
+            muted
+        );
+    }
+}
+
+/// Keep the fullscreen button in sync, including the fullscreen key.
+fn update_fullscreen_text(
+    window: Query<&Window, (With<PrimaryWindow>, Changed<Window>)>,
+    mut text: Query<&mut Text, With<FullscreenText>>,
+) {
+    let Ok(window) = window.get_single() else {
+        return;
+    };
+
+    for mut text in &mut text {
+        text.sections[0].value = fullscreen_label(window.mode);
+    }
+}
+
+fn cleanup_settings_menu(
+    mut commands: Commands,
+    interaction_query: Query<Entity, With<Button>>,
+    text_query: Query<Entity, With<Text>>,
+) {
+    for entity in &text_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    for entity in &interaction_query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
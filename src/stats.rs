@@ -0,0 +1,182 @@
+use bevy::prelude::*;
+use bevy_pkv::PkvStore;
+
+use crate::{camera::UI_LAYER, input::GamepadInput, DistanceTraveled, GameState};
+
+/// PkvStore key for how many runs have been played to the end.
+const RUNS_PLAYED_KEY: &str = "runs_played";
+/// PkvStore key for the distance covered across every run, in meters.
+const TOTAL_DISTANCE_KEY: &str = "total_distance";
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::GiveUp), record_run)
+            .add_systems(OnEnter(GameState::Victory), record_run)
+            .add_systems(OnEnter(GameState::Stats), setup_stats_menu)
+            .add_systems(Update, stats_menu_system.run_if(in_state(GameState::Stats)))
+            .add_systems(OnExit(GameState::Stats), cleanup_stats_menu);
+    }
+}
+
+/// Lifetime totals, for a sense of progress beyond the best run. Missing keys,
+/// as on first launch, count as zero.
+struct Stats {
+    runs_played: u32,
+    total_distance: f32,
+}
+
+impl Stats {
+    fn load(pkv: &PkvStore) -> Self {
+        Self {
+            runs_played: pkv.get::<u32>(RUNS_PLAYED_KEY).unwrap_or(0),
+            total_distance: pkv.get::<f32>(TOTAL_DISTANCE_KEY).unwrap_or(0.),
+        }
+    }
+
+    fn save(&self, pkv: &mut PkvStore) {
+        if let Err(err) = pkv.set(RUNS_PLAYED_KEY, &self.runs_played) {
+            warn!("failed to save runs played: {err}");
+        }
+        if let Err(err) = pkv.set(TOTAL_DISTANCE_KEY, &self.total_distance) {
+            warn!("failed to save total distance: {err}");
+        }
+    }
+}
+
+/// Count a run once it's over, whether the player gave up or made it.
+fn record_run(mut pkv: ResMut<PkvStore>, distance_traveled: Res<DistanceTraveled>) {
+    let mut stats = Stats::load(&pkv);
+    stats.runs_played += 1;
+    stats.total_distance += distance_traveled.0 / 64.;
+    stats.save(&mut pkv);
+}
+
+fn setup_stats_menu(mut commands: Commands, asset_server: Res<AssetServer>, pkv: Res<PkvStore>) {
+    let title_font: Handle<Font> = asset_server.load("fonts/Kaph-Regular.ttf");
+    let font = asset_server.load("fonts/PeaberryMono.ttf");
+
+    let text_style = TextStyle {
+        color: Color::WHITE,
+        font_size: 25.0,
+        font,
+    };
+
+    let stats = Stats::load(&pkv);
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            UI_LAYER,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "Stats".to_string(),
+                    TextStyle {
+                        font_size: 60.0,
+                        color: Color::WHITE,
+                        font: title_font,
+                    },
+                ),
+                UI_LAYER,
+            ));
+
+            parent.spawn((
+                TextBundle::from_section(
+                    format!(
+                        "Runs played: {}\nTotal distance: {:.0} m",
+                        stats.runs_played, stats.total_distance
+                    ),
+                    text_style.clone(),
+                )
+                .with_text_justify(JustifyText::Center)
+                .with_style(Style {
+                    margin: UiRect {
+                        top: Val::Px(20.),
+                        ..default()
+                    },
+                    ..default()
+                }),
+                UI_LAYER,
+            ));
+
+            parent
+                .spawn(ButtonBundle {
+                    background_color: Color::PURPLE.into(),
+                    style: Style {
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        width: Val::Px(150.),
+                        height: Val::Px(50.),
+                        margin: UiRect {
+                            top: Val::Px(20.),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Back".to_string(),
+                        text_style.clone(),
+                    ));
+                });
+        });
+}
+
+/// Buttons whose `Interaction` changed this frame.
+type ChangedButton = (Changed<Interaction>, With<Button>);
+
+fn stats_menu_system(
+    mut state: ResMut<NextState<GameState>>,
+    gamepad: GamepadInput,
+    interaction_query: Query<(&Interaction, &Children), ChangedButton>,
+    mut text_query: Query<&mut Text>,
+) {
+    if gamepad.just_pressed(GamepadButtonType::East) {
+        state.set(GameState::MainMenu);
+    }
+
+    for (interaction, children) in &interaction_query {
+        let mut text = text_query.get_mut(children[0]).unwrap();
+        match *interaction {
+            Interaction::Pressed => {
+                if text.sections[0].value == "Back" {
+                    state.set(GameState::MainMenu);
+                }
+            }
+            Interaction::Hovered => {
+                text.sections[0].style.font_size = 30.0;
+            }
+            Interaction::None => {
+                text.sections[0].style.font_size = 25.0;
+            }
+        }
+    }
+}
+
+fn cleanup_stats_menu(
+    mut commands: Commands,
+    interaction_query: Query<Entity, With<Button>>,
+    text_query: Query<Entity, With<Text>>,
+) {
+    for entity in &text_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    for entity in &interaction_query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,149 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{camera::UI_LAYER, GameState};
+
+/// Size of each on-screen button, in pixels.
+const TOUCH_BUTTON_SIZE: f32 = 80.;
+/// Gap between the on-screen buttons and the edges of the screen.
+const TOUCH_MARGIN: f32 = 16.;
+
+const COLOR_TOUCH_BUTTON: Color = Color::rgba(0.5, 0., 0.5, 0.5);
+
+pub struct TouchPlugin;
+
+impl Plugin for TouchPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TouchDetected>()
+            .add_systems(Update, detect_touch)
+            .add_systems(
+                Update,
+                (spawn_touch_controls, touch_pause_button).run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(OnExit(GameState::InGame), despawn_touch_controls);
+    }
+}
+
+/// Whether the screen has been touched, so the on-screen controls are worth
+/// showing. Desktop players never see them.
+#[derive(Resource, Default)]
+struct TouchDetected(bool);
+
+/// The on-screen controls, shown while playing.
+#[derive(Component)]
+struct TouchControls;
+
+#[derive(Component, Clone, Copy, PartialEq)]
+pub enum TouchButton {
+    Left,
+    Right,
+    Pause,
+}
+
+/// The on-screen buttons, read alongside the keyboard and gamepad.
+#[derive(SystemParam)]
+pub struct TouchButtons<'w, 's> {
+    buttons: Query<'w, 's, (&'static Interaction, &'static TouchButton)>,
+}
+
+impl TouchButtons<'_, '_> {
+    /// Whether `button` is being held down.
+    pub fn held(&self, button: TouchButton) -> bool {
+        self.buttons
+            .iter()
+            .any(|(interaction, which)| *which == button && *interaction == Interaction::Pressed)
+    }
+}
+
+fn detect_touch(touches: Res<Touches>, mut detected: ResMut<TouchDetected>) {
+    if !detected.0 && touches.any_just_pressed() {
+        detected.0 = true;
+    }
+}
+
+fn spawn_touch_controls(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    detected: Res<TouchDetected>,
+    controls: Query<(), With<TouchControls>>,
+) {
+    if !detected.0 || !controls.is_empty() {
+        return;
+    }
+
+    let text_style = TextStyle {
+        color: Color::WHITE,
+        font_size: 40.0,
+        font: asset_server.load("fonts/PeaberryMono.ttf"),
+    };
+
+    let button = |left: Val, right: Val| ButtonBundle {
+        background_color: COLOR_TOUCH_BUTTON.into(),
+        style: Style {
+            position_type: PositionType::Absolute,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            width: Val::Px(TOUCH_BUTTON_SIZE),
+            height: Val::Px(TOUCH_BUTTON_SIZE),
+            bottom: Val::Px(TOUCH_MARGIN),
+            left,
+            right,
+            ..default()
+        },
+        ..default()
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    ..default()
+                },
+                ..default()
+            },
+            UI_LAYER,
+            TouchControls,
+        ))
+        .with_children(|parent| {
+            for (which, label, left, right) in [
+                (TouchButton::Left, "<", Val::Px(TOUCH_MARGIN), Val::Auto),
+                (
+                    TouchButton::Right,
+                    ">",
+                    Val::Px(TOUCH_MARGIN * 2. + TOUCH_BUTTON_SIZE),
+                    Val::Auto,
+                ),
+                (TouchButton::Pause, "II", Val::Auto, Val::Px(TOUCH_MARGIN)),
+            ] {
+                parent
+                    .spawn((button(left, right), which))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            label.to_string(),
+                            text_style.clone(),
+                        ));
+                    });
+            }
+        });
+}
+
+fn touch_pause_button(
+    mut next_state: ResMut<NextState<GameState>>,
+    buttons: Query<(&Interaction, &TouchButton), Changed<Interaction>>,
+) {
+    let pressed = buttons.iter().any(|(interaction, which)| {
+        *which == TouchButton::Pause && *interaction == Interaction::Pressed
+    });
+    if pressed {
+        next_state.set(GameState::Pause);
+    }
+}
+
+/// Clear the controls off the menus; they come back when play resumes.
+fn despawn_touch_controls(mut commands: Commands, controls: Query<Entity, With<TouchControls>>) {
+    for entity in &controls {
+        commands.entity(entity).despawn_recursive();
+    }
+}
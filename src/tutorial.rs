@@ -0,0 +1,126 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_pkv::PkvStore;
+
+use crate::{
+    camera::UI_LAYER,
+    input::{key_label, KeyBindings},
+    GameState, PlayerInputEvent,
+};
+
+/// PkvStore key for whether the first-run hint has been shown.
+const SEEN_TUTORIAL_KEY: &str = "seen_tutorial";
+
+/// Seconds the hint stays up if the player doesn't move...
+const HINT_TIME: f32 = 6.;
+/// ...the last of which it spends fading out.
+const HINT_FADE_TIME: f32 = 1.;
+
+pub struct TutorialPlugin;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::InGame), show_tutorial_hint)
+            .add_systems(
+                Update,
+                update_tutorial_hint.run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(OnExit(GameState::InGame), hide_tutorial_hint);
+    }
+}
+
+/// The first-run hint, counting down until it's gone.
+#[derive(Component)]
+struct TutorialHint(Timer);
+
+/// Show the hint until the player has seen it through once. If they pause
+/// before then, it comes back when they resume.
+fn show_tutorial_hint(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    pkv: Res<PkvStore>,
+    bindings: Res<KeyBindings>,
+) {
+    if pkv.get::<bool>(SEEN_TUTORIAL_KEY).unwrap_or(false) {
+        return;
+    }
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            UI_LAYER,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    format!(
+                        "Hold {} to push uphill,\nwatch your fatigue!",
+                        key_label(bindings.move_right)
+                    ),
+                    TextStyle {
+                        color: Color::WHITE,
+                        font_size: 25.0,
+                        font: asset_server.load("fonts/PeaberryMono.ttf"),
+                    },
+                )
+                .with_text_justify(JustifyText::Center),
+                UI_LAYER,
+                TutorialHint(Timer::from_seconds(HINT_TIME, TimerMode::Once)),
+            ));
+        });
+}
+
+/// Fade the hint out once it's had its time, or straight away once the player
+/// gets moving, and remember not to show it again.
+fn update_tutorial_hint(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut pkv: ResMut<PkvStore>,
+    mut events: EventReader<PlayerInputEvent>,
+    mut hint: Query<(&mut TutorialHint, &mut Text, &Parent)>,
+) {
+    let Ok((mut hint, mut text, parent)) = hint.get_single_mut() else {
+        return;
+    };
+
+    let moved = events.read().any(|event| {
+        matches!(
+            event,
+            PlayerInputEvent::MoveLeft | PlayerInputEvent::MoveRight
+        )
+    });
+    let fade_start = Duration::from_secs_f32(HINT_TIME - HINT_FADE_TIME);
+    if moved && hint.0.elapsed() < fade_start {
+        hint.0.set_elapsed(fade_start);
+    }
+
+    hint.0.tick(time.delta());
+    if hint.0.finished() {
+        commands.entity(parent.get()).despawn_recursive();
+        if let Err(err) = pkv.set(SEEN_TUTORIAL_KEY, &true) {
+            warn!("failed to save tutorial flag: {err}");
+        }
+        return;
+    }
+
+    let alpha = (hint.0.remaining_secs() / HINT_FADE_TIME).min(1.);
+    text.sections[0].style.color.set_a(alpha);
+}
+
+/// Never leave the hint up over the pause or give-up menus.
+fn hide_tutorial_hint(mut commands: Commands, hint: Query<&Parent, With<TutorialHint>>) {
+    for parent in &hint {
+        commands.entity(parent.get()).despawn_recursive();
+    }
+}